@@ -14,7 +14,9 @@
 use std::os::raw::{c_char, c_float, c_int, c_void};
 
 // Re-export imgui types we need
-pub use arcdps_imgui_sys::{ImGuiWindowFlags, ImVec2, ImVec4};
+pub use arcdps_imgui_sys::{
+    igSetNextWindowPos, igSetNextWindowSize, ImGuiCond, ImGuiWindowFlags, ImVec2, ImVec4,
+};
 
 /// Opaque file dialog context
 #[repr(C)]
@@ -26,6 +28,13 @@ pub struct ImGuiFileDialog {
 pub type ImGuiFileDialogFlags = c_int;
 pub const ImGuiFileDialogFlags_None: ImGuiFileDialogFlags = 0;
 pub const ImGuiFileDialogFlags_ConfirmOverwrite: ImGuiFileDialogFlags = 1 << 0;
+pub const ImGuiFileDialogFlags_DontShowHiddenFiles: ImGuiFileDialogFlags = 1 << 1;
+pub const ImGuiFileDialogFlags_DisableCreateDirectoryButton: ImGuiFileDialogFlags = 1 << 2;
+pub const ImGuiFileDialogFlags_HideColumnType: ImGuiFileDialogFlags = 1 << 3;
+pub const ImGuiFileDialogFlags_HideColumnSize: ImGuiFileDialogFlags = 1 << 4;
+pub const ImGuiFileDialogFlags_HideColumnDate: ImGuiFileDialogFlags = 1 << 5;
+pub const ImGuiFileDialogFlags_Default: ImGuiFileDialogFlags =
+    ImGuiFileDialogFlags_ConfirmOverwrite;
 
 /// Callback function type for custom side pane
 pub type IGFD_PaneFun = Option<unsafe extern "C" fn(*const c_char, *mut c_void, *mut bool)>;
@@ -0,0 +1,570 @@
+//! Reading and writing serde documents through the dialog.
+
+#[cfg(not(any(feature = "json", feature = "toml", feature = "ron")))]
+compile_error!("the `serde` feature needs a document format: enable `json`, `toml` or `ron`");
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::{
+    BuilderTarget, ExtensionPolicy, FileDialog, FileDialogBuilder, Filter, FilterSet,
+    OpenDocumentBuilder, SaveDocumentBuilder, SaveTarget, WriteError,
+};
+
+/// A document format, chosen from the file extension or the selected filter.
+///
+/// Each format is only available with its feature: `json`, `toml` or `ron`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Codec {
+    /// JSON, `.json`.
+    #[cfg(feature = "json")]
+    Json,
+    /// TOML, `.toml`.
+    #[cfg(feature = "toml")]
+    Toml,
+    /// RON, `.ron`.
+    #[cfg(feature = "ron")]
+    Ron,
+}
+
+impl Codec {
+    /// Every enabled format.
+    pub const ALL: &'static [Codec] = &[
+        #[cfg(feature = "json")]
+        Codec::Json,
+        #[cfg(feature = "toml")]
+        Codec::Toml,
+        #[cfg(feature = "ron")]
+        Codec::Ron,
+    ];
+
+    /// Name of the format, e.g. `"JSON"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            #[cfg(feature = "json")]
+            Self::Json => "JSON",
+            #[cfg(feature = "toml")]
+            Self::Toml => "TOML",
+            #[cfg(feature = "ron")]
+            Self::Ron => "RON",
+        }
+    }
+
+    /// Extension of files in this format, including the leading `.`.
+    pub fn extension(self) -> &'static str {
+        match self {
+            #[cfg(feature = "json")]
+            Self::Json => ".json",
+            #[cfg(feature = "toml")]
+            Self::Toml => ".toml",
+            #[cfg(feature = "ron")]
+            Self::Ron => ".ron",
+        }
+    }
+
+    /// The format of an extension given with or without the leading `.`,
+    /// ignoring case.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        let extension = extension.strip_prefix('.').unwrap_or(extension);
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|codec| codec.extension()[1..].eq_ignore_ascii_case(extension))
+    }
+
+    /// The format of the file at `path`, from its extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        Self::from_extension(path.extension()?.to_str()?)
+    }
+
+    /// The format of `filter`, if all its extensions are in that format.
+    ///
+    /// Filters mixing formats, such as the "Documents" group of
+    /// [`open_filters`](Self::open_filters), have none.
+    pub fn from_filter(filter: &Filter) -> Option<Self> {
+        let mut codecs = filter
+            .extensions()
+            .iter()
+            .map(|extension| Self::from_extension(extension));
+        let first = codecs.next()??;
+        codecs.all(|codec| codec == Some(first)).then_some(first)
+    }
+
+    /// Filters offered by [`FileDialog::open_document`]: all formats together
+    /// if there are several, then one per format.
+    pub fn open_filters() -> FilterSet {
+        let all = (Self::ALL.len() > 1)
+            .then(|| Filter::group("Documents", Self::ALL.iter().map(|codec| codec.extension())));
+        all.into_iter()
+            .chain(Self::save_filters().filters().iter().cloned())
+            .collect()
+    }
+
+    /// Filters offered by [`FileDialog::save_document`]: one per format.
+    pub fn save_filters() -> FilterSet {
+        Self::ALL
+            .iter()
+            .map(|codec| Filter::extension(codec.extension()))
+            .collect()
+    }
+
+    /// Parse `text` as a document in this format.
+    fn decode<T: DeserializeOwned>(self, text: &str) -> Result<T, (String, Option<Position>)> {
+        match self {
+            #[cfg(feature = "json")]
+            Self::Json => serde_json::from_str(text).map_err(|e| {
+                let message = e.to_string();
+                if e.line() == 0 {
+                    return (message, None);
+                }
+                // The message ends with the position, which is reported separately.
+                let suffix = format!(" at line {} column {}", e.line(), e.column());
+                let message = message.strip_suffix(&suffix).unwrap_or(&message).to_owned();
+                let position = Position {
+                    line: e.line(),
+                    column: e.column(),
+                };
+                (message, Some(position))
+            }),
+            #[cfg(feature = "toml")]
+            Self::Toml => toml::from_str(text).map_err(|e| {
+                let position = e.span().map(|span| Position::at_offset(text, span.start));
+                (e.message().to_owned(), position)
+            }),
+            #[cfg(feature = "ron")]
+            Self::Ron => ron::from_str(text).map_err(|e| {
+                let position = Position {
+                    line: e.position.line,
+                    column: e.position.col,
+                };
+                (e.code.to_string(), Some(position))
+            }),
+        }
+    }
+
+    /// Serialize `value` in this format.
+    fn encode<T: Serialize + ?Sized>(self, value: &T) -> Result<String, String> {
+        match self {
+            #[cfg(feature = "json")]
+            Self::Json => serde_json::to_string_pretty(value).map_err(|e| e.to_string()),
+            #[cfg(feature = "toml")]
+            Self::Toml => toml::to_string_pretty(value).map_err(|e| e.to_string()),
+            #[cfg(feature = "ron")]
+            Self::Ron => ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())
+                .map_err(|e| e.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Codec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A 1-based line and column in a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Position {
+    /// Line, starting at 1.
+    pub line: usize,
+    /// Column in characters, starting at 1.
+    pub column: usize,
+}
+
+impl Position {
+    /// The position of the byte `offset` in `text`.
+    #[cfg(feature = "toml")]
+    fn at_offset(text: &str, offset: usize) -> Self {
+        let before = &text[..offset.min(text.len())];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}", self.line, self.column)
+    }
+}
+
+/// Error of reading or writing a document through
+/// [`FileDialog::open_document`] or [`FileDialog::save_document`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum DocumentError {
+    /// The file extension is not one of an enabled [`Codec`], or the file has
+    /// no extension and the selected filter has no single format.
+    UnknownFormat {
+        /// Path of the file.
+        path: PathBuf,
+    },
+    /// The file could not be read.
+    Read {
+        /// Path of the file.
+        path: PathBuf,
+        /// The underlying error.
+        source: io::Error,
+    },
+    /// The file is not a valid document of the expected type.
+    Parse {
+        /// Path of the file.
+        path: PathBuf,
+        /// Format the file was parsed as.
+        codec: Codec,
+        /// What is wrong, as reported by the format.
+        message: String,
+        /// Where in the file, if known.
+        position: Option<Position>,
+    },
+    /// The value cannot be written in the chosen format, e.g. TOML without a
+    /// table at the top.
+    Serialize {
+        /// Format the value was written as.
+        codec: Codec,
+        /// What is wrong, as reported by the format.
+        message: String,
+    },
+    /// The file could not be written.
+    Write(WriteError),
+}
+
+impl fmt::Display for DocumentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownFormat { path } => {
+                write!(f, "unknown document format: {}", path.display())
+            }
+            Self::Read { path, source } => {
+                write!(f, "cannot read {}: {}", path.display(), source)
+            }
+            Self::Parse {
+                path,
+                codec,
+                message,
+                position: Some(position),
+            } => write!(
+                f,
+                "invalid {} in {} at {}: {}",
+                codec,
+                path.display(),
+                position,
+                message
+            ),
+            Self::Parse {
+                path,
+                codec,
+                message,
+                position: None,
+            } => write!(f, "invalid {} in {}: {}", codec, path.display(), message),
+            Self::Serialize { codec, message } => {
+                write!(f, "cannot write as {}: {}", codec, message)
+            }
+            Self::Write(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for DocumentError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Read { source, .. } => Some(source),
+            Self::Write(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<WriteError> for DocumentError {
+    fn from(e: WriteError) -> Self {
+        Self::Write(e)
+    }
+}
+
+/// A value to save, serialized once the format is known.
+pub(crate) struct PendingDocument(Box<dyn Fn(Codec) -> Result<String, String> + Send>);
+
+impl PendingDocument {
+    pub(crate) fn new<T: Serialize + Send + 'static>(value: T) -> Self {
+        Self(Box::new(move |codec| codec.encode(&value)))
+    }
+}
+
+/// The format of `path`, or of the selected filter if `path` has no extension.
+fn codec_for(path: &Path, filter: Option<&Filter>) -> Result<Codec, DocumentError> {
+    let codec = match path.extension() {
+        Some(_) => Codec::from_path(path),
+        None => filter.and_then(Codec::from_filter),
+    };
+    codec.ok_or_else(|| DocumentError::UnknownFormat {
+        path: path.to_owned(),
+    })
+}
+
+impl FileDialog {
+    /// Read the document chosen in a confirmed [`open_document`](Self::open_document) dialog.
+    pub(crate) fn read_document<T: DeserializeOwned>(
+        &self,
+        path: &Path,
+    ) -> Result<T, DocumentError> {
+        let codec = codec_for(path, self.selected_filter())?;
+        let text = fs::read_to_string(path).map_err(|source| DocumentError::Read {
+            path: path.to_owned(),
+            source,
+        })?;
+        codec
+            .decode(&text)
+            .map_err(|(message, position)| DocumentError::Parse {
+                path: path.to_owned(),
+                codec,
+                message,
+                position,
+            })
+    }
+
+    /// Write the value of a confirmed [`save_document`](Self::save_document) dialog.
+    pub(crate) fn write_document(&self, target: &SaveTarget) -> Result<(), DocumentError> {
+        let codec = codec_for(target.path(), self.selected_filter())?;
+        let document = self
+            .data
+            .and_then(|data| unsafe { (*data.as_ptr()).document.as_ref() })
+            .ok_or_else(|| DocumentError::UnknownFormat {
+                path: target.path().to_owned(),
+            })?;
+        let text =
+            (document.0)(codec).map_err(|message| DocumentError::Serialize { codec, message })?;
+        Ok(target.write_bytes(text.as_bytes())?)
+    }
+}
+
+impl FileDialog {
+    /// Open a dialog that reads a document into `T`.
+    ///
+    /// The format is chosen from the extension of the chosen file, or from the
+    /// selected filter if the file has no extension. Polling the returned handle
+    /// gives the parsed value, or a [`DocumentError`] to show to the user.
+    /// The dialog offers [`Codec::open_filters`] unless
+    /// [`filters`](crate::FileDialogBuilder::filters) are set.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use imgui_filedialog::{DialogOutcome, FileDialog};
+    ///
+    /// #[derive(serde::Deserialize)]
+    /// struct Settings {
+    ///     volume: f32,
+    /// }
+    ///
+    /// # fn main() -> Result<(), imgui_filedialog::FileDialogError> {
+    /// # let mut dialog = FileDialog::new();
+    /// let handle = dialog.open_document::<Settings>().build("load_settings")?;
+    ///
+    /// // Every frame:
+    /// match dialog.poll(&handle, [400.0, 300.0], [800.0, 600.0]) {
+    ///     DialogOutcome::Accepted { value: Ok(settings), .. } => println!("volume {}", settings.volume),
+    ///     DialogOutcome::Accepted { value: Err(e), .. } => eprintln!("{}", e),
+    ///     _ => {}
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn open_document<T: DeserializeOwned>(&mut self) -> OpenDocumentBuilder<'_, T> {
+        FileDialogBuilder::new(BuilderTarget::Dialog(self)).filter_set(Codec::open_filters())
+    }
+
+    /// Open a dialog that writes `value` as a document.
+    ///
+    /// The dialog keeps `value` until it is confirmed, and then serializes it
+    /// in the chosen format only. The format is chosen from the extension of
+    /// the typed name, or from the selected filter, whose extension is appended
+    /// to names typed without one. Polling the returned
+    /// handle writes the file with [`SaveTarget::write_bytes`] and gives the
+    /// target, or a [`DocumentError`] to show to the user.
+    ///
+    /// The file is only written by [`FileDialog::poll`] or
+    /// [`FileDialog::poll_with`]: a dialog finished through
+    /// [`FileDialog::display`] and closed writes nothing, which is why the
+    /// builder has no `on_complete`, `send_to` or `build_future`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use imgui_filedialog::{DialogOutcome, FileDialog};
+    ///
+    /// #[derive(serde::Serialize)]
+    /// struct Settings {
+    ///     volume: f32,
+    /// }
+    ///
+    /// # fn main() -> Result<(), imgui_filedialog::FileDialogError> {
+    /// # let mut dialog = FileDialog::new();
+    /// let settings = Settings { volume: 0.8 };
+    /// let handle = dialog
+    ///     .save_document(settings)
+    ///     .confirm_overwrite()
+    ///     .build("save_settings")?;
+    ///
+    /// // Every frame:
+    /// if let DialogOutcome::Accepted { value: Err(e), .. } = dialog.poll(&handle, [400.0, 300.0], [800.0, 600.0]) {
+    ///     eprintln!("{}", e);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn save_document<T: Serialize + Send + 'static>(
+        &mut self,
+        value: T,
+    ) -> SaveDocumentBuilder<'_> {
+        let mut builder = FileDialogBuilder::new(BuilderTarget::Dialog(self))
+            .filter_set(Codec::save_filters())
+            .extension_policy(ExtensionPolicy::AppendFilterExtension);
+        builder.document = Some(PendingDocument::new(value));
+        builder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Settings {
+        name: String,
+        volume: f32,
+        tags: Vec<String>,
+    }
+
+    fn settings() -> Settings {
+        Settings {
+            name: "arc \"dps\"\n\tlog".to_owned(),
+            volume: 0.5,
+            tags: vec!["raid".to_owned(), String::new()],
+        }
+    }
+
+    fn round_trip(codec: Codec) {
+        let text = codec.encode(&settings()).unwrap();
+        assert_eq!(codec.decode::<Settings>(&text).unwrap(), settings());
+    }
+
+    #[cfg(feature = "json")]
+    fn unknown_format(result: Result<Codec, DocumentError>) -> bool {
+        matches!(result, Err(DocumentError::UnknownFormat { .. }))
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_round_trips() {
+        round_trip(Codec::Json);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_errors_report_the_position_separately() {
+        let (message, position) = Codec::Json
+            .decode::<Settings>("{\n  \"name\": 1\n}")
+            .unwrap_err();
+        assert_eq!(position.map(|p| p.line), Some(2));
+        assert!(!message.contains("line"), "{}", message);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml_round_trips() {
+        round_trip(Codec::Toml);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml_errors_have_a_position() {
+        let (_, position) = Codec::Toml
+            .decode::<Settings>("volume = 0.5\nname = 1\ntags = []\n")
+            .unwrap_err();
+        assert_eq!(position.map(|p| p.line), Some(2));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn toml_needs_a_table() {
+        assert!(Codec::Toml.encode(&5).is_err());
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn positions_count_lines_and_characters() {
+        assert_eq!(
+            Position::at_offset("ab\ncdé\nf", 7),
+            Position { line: 2, column: 4 }
+        );
+        assert_eq!(
+            Position::at_offset("ab", 100),
+            Position { line: 1, column: 3 }
+        );
+    }
+
+    #[cfg(feature = "ron")]
+    #[test]
+    fn ron_round_trips() {
+        round_trip(Codec::Ron);
+    }
+
+    #[cfg(feature = "ron")]
+    #[test]
+    fn ron_errors_have_a_position() {
+        let (_, position) = Codec::Ron
+            .decode::<Settings>("(\n  name: 1,\n)")
+            .unwrap_err();
+        assert_eq!(position.map(|p| p.line), Some(2));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn extensions_ignore_case_and_the_dot() {
+        assert_eq!(Codec::from_extension(".JSON"), Some(Codec::Json));
+        assert_eq!(Codec::from_extension("json"), Some(Codec::Json));
+        assert_eq!(Codec::from_extension(".jsonc"), None);
+        assert_eq!(
+            Codec::from_filter(&Filter::extension(".json").case_insensitive()),
+            Some(Codec::Json)
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn the_extension_wins_over_the_filter() {
+        let json = Filter::extension(".json");
+        assert_eq!(codec_for(Path::new("a.JSON"), None).unwrap(), Codec::Json);
+        assert_eq!(
+            codec_for(Path::new("settings"), Some(&json)).unwrap(),
+            Codec::Json
+        );
+        assert!(unknown_format(codec_for(Path::new("a.txt"), Some(&json))));
+        assert!(unknown_format(codec_for(Path::new("settings"), None)));
+    }
+
+    #[cfg(all(feature = "json", feature = "toml"))]
+    #[test]
+    fn mixed_filters_do_not_pick_a_format() {
+        let filters = Codec::open_filters();
+        let documents = &filters.filters()[0];
+        assert_eq!(documents.name(), "Documents");
+        assert_eq!(Codec::from_filter(documents), None);
+        assert!(unknown_format(codec_for(
+            Path::new("settings"),
+            Some(documents)
+        )));
+        assert_eq!(filters.len(), Codec::ALL.len() + 1);
+        assert_eq!(Codec::save_filters().len(), Codec::ALL.len());
+    }
+}
@@ -0,0 +1,272 @@
+//! Delivery of dialog results outside of polling.
+
+use std::any::Any;
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll, Waker};
+
+use crate::{DialogHandle, DialogOutcome, FileDialogError};
+
+/// Where the result of a dialog goes once it finishes.
+///
+/// Dialogs with a completion are closed by [`FileDialog::display`](crate::FileDialog::display)
+/// as soon as they finish. If a dialog goes away without finishing, e.g. when it
+/// is replaced or its [`FileDialog`](crate::FileDialog) is dropped, it completes as cancelled.
+pub(crate) enum Completion {
+    Future(Arc<Mutex<FutureState>>),
+    Callback(Box<dyn FnOnce(DialogEvent) + Send>),
+    Channel(Sender<DialogEvent>),
+}
+
+impl Completion {
+    /// Returns `true` if nobody is waiting for the result anymore.
+    pub(crate) fn is_abandoned(&self) -> bool {
+        match self {
+            Self::Future(state) => lock(state).dropped,
+            Self::Callback(_) | Self::Channel(_) => false,
+        }
+    }
+
+    /// Deliver the outcome of a finished dialog, holding the paths of its mode.
+    pub(crate) fn complete(self, handle: &DialogHandle, outcome: DialogOutcome<Vec<PathBuf>>) {
+        match self {
+            Self::Callback(callback) => callback(DialogEvent::new(handle, outcome)),
+            Self::Channel(sender) => {
+                // A dropped receiver means nobody is interested anymore.
+                let _ = sender.send(DialogEvent::new(handle, outcome));
+            }
+            Self::Future(state) => {
+                let mut state = lock(&state);
+                state.result = Some(match outcome {
+                    DialogOutcome::Accepted { value, .. } => Some(value),
+                    DialogOutcome::Pending
+                    | DialogOutcome::Cancelled
+                    | DialogOutcome::Failed(_) => None,
+                });
+                if let Some(waker) = state.waker.take() {
+                    waker.wake();
+                }
+            }
+        }
+    }
+}
+
+/// Result of a finished dialog, with owned paths.
+///
+/// Delivered by [`FileDialogBuilder::on_complete`](crate::FileDialogBuilder::on_complete)
+/// and [`FileDialogBuilder::send_to`](crate::FileDialogBuilder::send_to).
+#[derive(Debug)]
+pub enum DialogEvent {
+    /// The user confirmed the selection.
+    Accepted {
+        /// The handle of the dialog.
+        handle: DialogHandle,
+        /// The chosen paths: the selected files, the file to save to or the
+        /// chosen directory, depending on the builder.
+        paths: Vec<PathBuf>,
+        /// The value attached with
+        /// [`FileDialogBuilder::user_data`](crate::FileDialogBuilder::user_data).
+        user_data: Option<Box<dyn Any + Send>>,
+    },
+    /// The user cancelled the dialog, or it was closed before finishing.
+    Cancelled {
+        /// The handle of the dialog.
+        handle: DialogHandle,
+    },
+    /// The user confirmed the dialog, but its result cannot be represented.
+    Failed {
+        /// The handle of the dialog.
+        handle: DialogHandle,
+        /// Why the result cannot be read.
+        error: FileDialogError,
+    },
+}
+
+impl DialogEvent {
+    fn new(handle: &DialogHandle, outcome: DialogOutcome<Vec<PathBuf>>) -> Self {
+        let handle = handle.clone();
+        match outcome {
+            DialogOutcome::Accepted { value, user_data } => Self::Accepted {
+                handle,
+                paths: value,
+                user_data,
+            },
+            DialogOutcome::Pending | DialogOutcome::Cancelled => Self::Cancelled { handle },
+            DialogOutcome::Failed(error) => Self::Failed { handle, error },
+        }
+    }
+
+    /// The handle of the dialog.
+    pub fn handle(&self) -> &DialogHandle {
+        match self {
+            Self::Accepted { handle, .. }
+            | Self::Cancelled { handle }
+            | Self::Failed { handle, .. } => handle,
+        }
+    }
+
+    /// The chosen paths, empty if the dialog was cancelled or failed.
+    pub fn paths(&self) -> &[PathBuf] {
+        match self {
+            Self::Accepted { paths, .. } => paths,
+            Self::Cancelled { .. } | Self::Failed { .. } => &[],
+        }
+    }
+
+    /// The user data of an accepted dialog, if it is a `U`.
+    pub fn user_data<U: Any>(&self) -> Option<&U> {
+        match self {
+            Self::Accepted {
+                user_data: Some(data),
+                ..
+            } => data.downcast_ref(),
+            Self::Accepted { .. } | Self::Cancelled { .. } | Self::Failed { .. } => None,
+        }
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct FutureState {
+    result: Option<Option<Vec<PathBuf>>>,
+    waker: Option<Waker>,
+    dropped: bool,
+}
+
+/// A dialog whose result can be awaited.
+///
+/// Created by [`FileDialogBuilder::build_future`](crate::FileDialogBuilder::build_future).
+/// The future resolves while the dialog is displayed every frame, typically by
+/// [`DialogManager::render`](crate::DialogManager::render), so it works with any executor.
+/// It resolves to the chosen paths: the selected files, the file to save to or
+/// the chosen directory, depending on the builder. It resolves to `None` if the
+/// dialog is cancelled or closed, or its result cannot be represented.
+///
+/// Dropping the future cancels the dialog; it is closed the next time it is displayed.
+#[must_use = "dropping a DialogFuture cancels its dialog"]
+pub struct DialogFuture {
+    handle: DialogHandle,
+    state: Arc<Mutex<FutureState>>,
+}
+
+impl DialogFuture {
+    pub(crate) fn new(handle: DialogHandle, state: Arc<Mutex<FutureState>>) -> Self {
+        Self { handle, state }
+    }
+
+    pub(crate) fn completion() -> (Completion, Arc<Mutex<FutureState>>) {
+        let state = Arc::new(Mutex::new(FutureState::default()));
+        (Completion::Future(state.clone()), state)
+    }
+
+    /// The handle of the dialog.
+    pub fn handle(&self) -> &DialogHandle {
+        &self.handle
+    }
+}
+
+impl Future for DialogFuture {
+    type Output = Option<Vec<PathBuf>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = lock(&self.state);
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for DialogFuture {
+    fn drop(&mut self) {
+        lock(&self.state).dropped = true;
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    fn handle() -> DialogHandle {
+        DialogHandle::new("export").unwrap()
+    }
+
+    #[test]
+    fn outcomes_map_to_events() {
+        let paths = vec![PathBuf::from("out/report.csv")];
+        let outcome = DialogOutcome::Accepted {
+            value: paths.clone(),
+            user_data: Some(Box::new(7_u32)),
+        };
+        let event = DialogEvent::new(&handle(), outcome);
+        assert!(matches!(&event, DialogEvent::Accepted { paths: p, .. } if *p == paths));
+        assert_eq!(event.paths(), paths);
+        assert_eq!(event.user_data::<u32>(), Some(&7));
+        assert_eq!(event.user_data::<String>(), None);
+        assert_eq!(event.handle().key(), "export");
+
+        for outcome in [DialogOutcome::Cancelled, DialogOutcome::Pending] {
+            let event = DialogEvent::new(&handle(), outcome);
+            assert!(matches!(event, DialogEvent::Cancelled { .. }));
+            assert!(event.paths().is_empty());
+            assert_eq!(event.user_data::<u32>(), None);
+        }
+
+        let error = FileDialogError::NonUnicodePath(vec![0xff]);
+        let event = DialogEvent::new(&handle(), DialogOutcome::Failed(error.clone()));
+        assert!(matches!(&event, DialogEvent::Failed { error: e, .. } if *e == error));
+        assert!(event.paths().is_empty());
+    }
+
+    #[test]
+    fn channels_and_callbacks_receive_the_event() {
+        let (sender, receiver) = mpsc::channel();
+        Completion::Channel(sender).complete(
+            &handle(),
+            DialogOutcome::Accepted {
+                value: vec![PathBuf::from("logs")],
+                user_data: None,
+            },
+        );
+        assert_eq!(receiver.recv().unwrap().paths(), [PathBuf::from("logs")]);
+
+        let received = Arc::new(Mutex::new(None));
+        let slot = received.clone();
+        Completion::Callback(Box::new(move |event| *lock(&slot) = Some(event)))
+            .complete(&handle(), DialogOutcome::Cancelled);
+        assert!(matches!(
+            *lock(&received),
+            Some(DialogEvent::Cancelled { .. })
+        ));
+    }
+
+    #[test]
+    fn futures_resolve_to_the_paths() {
+        let (completion, state) = DialogFuture::completion();
+        completion.complete(
+            &handle(),
+            DialogOutcome::Accepted {
+                value: vec![PathBuf::from("a.txt")],
+                user_data: None,
+            },
+        );
+        assert_eq!(
+            lock(&state).result,
+            Some(Some(vec![PathBuf::from("a.txt")]))
+        );
+
+        let (completion, state) = DialogFuture::completion();
+        completion.complete(&handle(), DialogOutcome::Cancelled);
+        assert_eq!(lock(&state).result, Some(None));
+    }
+}
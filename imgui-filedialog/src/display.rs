@@ -0,0 +1,124 @@
+//! Window options applied when displaying a dialog.
+
+use arcdps_imgui::{Condition, WindowFlags};
+
+use crate::sys;
+
+/// Window options used by [`FileDialog::display_with`](crate::FileDialog::display_with).
+///
+/// # Example
+///
+/// ```no_run
+/// use arcdps_imgui::{Condition, WindowFlags};
+/// use imgui_filedialog::DisplayOptions;
+///
+/// let options = DisplayOptions::new()
+///     .flags(WindowFlags::NO_COLLAPSE)
+///     .min_size([400.0, 300.0])
+///     .size([600.0, 400.0], Condition::FirstUseEver)
+///     .position([960.0, 540.0], Condition::Appearing)
+///     .pivot([0.5, 0.5]);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct DisplayOptions {
+    flags: WindowFlags,
+    min_size: [f32; 2],
+    max_size: [f32; 2],
+    size: Option<([f32; 2], Condition)>,
+    position: Option<([f32; 2], Condition)>,
+    pivot: [f32; 2],
+}
+
+impl Default for DisplayOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DisplayOptions {
+    /// Options without flags, size constraints or positioning.
+    pub fn new() -> Self {
+        Self {
+            flags: WindowFlags::empty(),
+            min_size: [0.0, 0.0],
+            max_size: [f32::MAX, f32::MAX],
+            size: None,
+            position: None,
+            pivot: [0.0, 0.0],
+        }
+    }
+
+    /// Set the window flags of the dialog window.
+    pub fn flags(mut self, flags: WindowFlags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Set the minimum dialog size `[width, height]`.
+    pub fn min_size(mut self, min_size: [f32; 2]) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    /// Set the maximum dialog size `[width, height]`.
+    pub fn max_size(mut self, max_size: [f32; 2]) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Set the dialog size `[width, height]` under the given condition.
+    pub fn size(mut self, size: [f32; 2], condition: Condition) -> Self {
+        self.size = Some((size, condition));
+        self
+    }
+
+    /// Set the dialog position `[x, y]` under the given condition.
+    pub fn position(mut self, position: [f32; 2], condition: Condition) -> Self {
+        self.position = Some((position, condition));
+        self
+    }
+
+    /// Set which point of the window is placed at the position.
+    ///
+    /// `[0.0, 0.0]` (the default) is the top-left corner, `[0.5, 0.5]` centers
+    /// the window on the position.
+    pub fn pivot(mut self, pivot: [f32; 2]) -> Self {
+        self.pivot = pivot;
+        self
+    }
+
+    /// Issue the `SetNextWindow*` calls for the dialog window.
+    ///
+    /// Only call this when the dialog window is drawn right after, otherwise the
+    /// calls apply to the next window of the caller.
+    pub(crate) fn apply_next_window(&self) {
+        unsafe {
+            if let Some((size, condition)) = self.size {
+                if condition != Condition::Never {
+                    sys::igSetNextWindowSize(im_vec2(size), condition as sys::ImGuiCond);
+                }
+            }
+            if let Some((position, condition)) = self.position {
+                if condition != Condition::Never {
+                    sys::igSetNextWindowPos(
+                        im_vec2(position),
+                        condition as sys::ImGuiCond,
+                        im_vec2(self.pivot),
+                    );
+                }
+            }
+        }
+    }
+
+    pub(crate) fn window_flags(&self) -> sys::ImGuiWindowFlags {
+        self.flags.bits() as sys::ImGuiWindowFlags
+    }
+
+    pub(crate) fn size_constraints(&self) -> (sys::ImVec2, sys::ImVec2) {
+        (im_vec2(self.min_size), im_vec2(self.max_size))
+    }
+}
+
+fn im_vec2(v: [f32; 2]) -> sys::ImVec2 {
+    sys::ImVec2 { x: v[0], y: v[1] }
+}
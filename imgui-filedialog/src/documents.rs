@@ -0,0 +1,311 @@
+//! Document types: filters and styles defined in one place.
+
+use std::path::Path;
+
+use crate::{ExtensionStyle, ExtensionStyles, FileDialog, FileDialogError, Filter, FilterSet};
+
+/// A kind of file the addon reads or writes, e.g. "arcdps logs".
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentType {
+    name: String,
+    extensions: Vec<String>,
+    color: [f32; 4],
+    icon: Option<String>,
+    case_insensitive: bool,
+}
+
+impl DocumentType {
+    /// Create a type with its name and extensions, e.g. `[".evtc", ".zevtc"]`.
+    ///
+    /// As in ImGuiFileDialog, the extension of a file is everything from the last
+    /// `.` of its name, so an extension such as `.tar.gz` never matches and is
+    /// rejected by [`FileDialog::apply_document_types`].
+    /// Files are shown in white until a [`color`](Self::color) is set.
+    pub fn new<I, S>(name: impl Into<String>, extensions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            name: name.into(),
+            extensions: extensions.into_iter().map(Into::into).collect(),
+            color: [1.0, 1.0, 1.0, 1.0],
+            icon: None,
+            case_insensitive: false,
+        }
+    }
+
+    /// Set the RGBA color of matching files.
+    pub fn color(mut self, color: [f32; 4]) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Set the icon/text prefix of matching files. An empty icon clears it.
+    pub fn icon(mut self, icon: &str) -> Self {
+        self.icon = (!icon.is_empty()).then(|| icon.to_owned());
+        self
+    }
+
+    /// Also match the lowercase and uppercase spelling of every extension.
+    ///
+    /// See [`Filter::case_insensitive`].
+    pub fn case_insensitive(mut self) -> Self {
+        self.case_insensitive = true;
+        self
+    }
+
+    /// Get the name, also used as the filter name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the extensions as given.
+    pub fn extensions(&self) -> &[String] {
+        &self.extensions
+    }
+
+    /// Get the filter selecting this type.
+    pub fn filter(&self) -> Filter {
+        let filter = Filter::group(self.name.clone(), self.extensions.iter().cloned());
+        if self.case_insensitive {
+            filter.case_insensitive()
+        } else {
+            filter
+        }
+    }
+
+    /// Get the style of files of this type.
+    pub fn style(&self) -> ExtensionStyle {
+        ExtensionStyle {
+            color: self.color,
+            icon: self.icon.clone(),
+        }
+    }
+
+    /// Check if a path has one of the extensions of this type, the way
+    /// ImGuiFileDialog matches a filter.
+    ///
+    /// Returns the rank of the match, so an exact extension wins over `.*`.
+    fn match_rank(&self, path: &Path) -> Option<u8> {
+        let name = path.file_name()?.to_string_lossy();
+        // ImGuiFileDialog takes the extension from the last `.` and compares it
+        // exactly with every spelling of the filter.
+        let extension = name.rfind('.').map(|i| &name[i..]);
+        let spellings = self.filter().spellings();
+        match extension {
+            Some(extension) if spellings.iter().any(|s| s == extension) => Some(1),
+            _ if spellings.iter().any(|s| s == ".*") => Some(0),
+            _ => None,
+        }
+    }
+}
+
+/// Registry of the [`DocumentType`]s an addon works with.
+///
+/// Produces both the filters for [`FileDialogBuilder::filter_set`](crate::FileDialogBuilder::filter_set)
+/// and the extension styles of a [`FileDialog`], so the two cannot drift apart.
+///
+/// # Example
+///
+/// ```no_run
+/// use imgui_filedialog::{DocumentType, DocumentTypes, FileDialog};
+///
+/// # fn main() -> Result<(), imgui_filedialog::FileDialogError> {
+/// let types = DocumentTypes::new()
+///     .with(DocumentType::new("Logs", [".evtc", ".zevtc"]).color([1.0, 0.8, 0.0, 1.0]).icon("[log]"))
+///     .with(DocumentType::new("Archives", [".zip"]).color([0.6, 0.6, 1.0, 1.0]));
+///
+/// let mut dialog = FileDialog::new();
+/// dialog.apply_document_types(&types)?;
+/// let handle = dialog
+///     .open_file()
+///     .filter_set(types.filters().all_files())
+///     .build("open_log")?;
+///
+/// // Later, once the dialog is accepted:
+/// # let path = std::path::Path::new("20240101.zevtc");
+/// if let Some(kind) = types.classify(path) {
+///     println!("{} is one of the {}", path.display(), kind.name());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DocumentTypes {
+    types: Vec<DocumentType>,
+}
+
+impl DocumentTypes {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a type, consuming and returning the registry.
+    pub fn with(mut self, document_type: DocumentType) -> Self {
+        self.push(document_type);
+        self
+    }
+
+    /// Add a type.
+    pub fn push(&mut self, document_type: DocumentType) {
+        self.types.push(document_type);
+    }
+
+    /// Get a type by name.
+    pub fn get(&self, name: &str) -> Option<&DocumentType> {
+        self.types.iter().find(|t| t.name == name)
+    }
+
+    /// Number of types.
+    pub fn len(&self) -> usize {
+        self.types.len()
+    }
+
+    /// Returns `true` if there are no types.
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+
+    /// Iterate over the types in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = &DocumentType> + '_ {
+        self.types.iter()
+    }
+
+    /// Get one filter per type, in order.
+    ///
+    /// Add [`FilterSet::all_files`] to also offer all files.
+    pub fn filters(&self) -> FilterSet {
+        self.types.iter().map(DocumentType::filter).collect()
+    }
+
+    /// Get the styles of all extensions, including case-expanded spellings.
+    ///
+    /// Where types share an extension, the type added first wins.
+    pub fn extension_styles(&self) -> ExtensionStyles {
+        let mut styles = ExtensionStyles::new();
+        for document_type in self.types.iter().rev() {
+            let style = document_type.style();
+            for extension in document_type.filter().spellings() {
+                if extension == ".*" {
+                    continue;
+                }
+                styles.insert(&extension, style.clone());
+            }
+        }
+        styles
+    }
+
+    /// Find the type of a path returned by a dialog.
+    ///
+    /// Matches like the filters of the dialog: a type with the extension of the
+    /// path wins over one with `.*`; among equal matches, the type added first.
+    pub fn classify(&self, path: &Path) -> Option<&DocumentType> {
+        let mut best: Option<(u8, &DocumentType)> = None;
+        for document_type in &self.types {
+            if let Some(rank) = document_type.match_rank(path) {
+                if !matches!(best, Some((best_rank, _)) if best_rank >= rank) {
+                    best = Some((rank, document_type));
+                }
+            }
+        }
+        best.map(|(_, document_type)| document_type)
+    }
+
+    /// Check that every extension can match a file.
+    fn check(&self) -> Result<(), FileDialogError> {
+        let multi_dot = self
+            .types
+            .iter()
+            .flat_map(|t| &t.extensions)
+            .any(|extension| extension.matches('.').count() > 1);
+        if multi_dot {
+            return Err(FileDialogError::Misuse(
+                "document type extensions must contain a single `.`",
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl FileDialog {
+    /// Set the extension styles of all document types.
+    ///
+    /// Styles already set for other extensions are kept. Fails without changing
+    /// any style if an extension contains more than one `.`, e.g. `.tar.gz`.
+    pub fn apply_document_types(&mut self, types: &DocumentTypes) -> Result<(), FileDialogError> {
+        types.check()?;
+        self.apply_extension_styles(&types.extension_styles())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classify<'a>(types: &'a DocumentTypes, path: &str) -> Option<&'a str> {
+        types.classify(Path::new(path)).map(DocumentType::name)
+    }
+
+    #[test]
+    fn classify_uses_the_last_extension() {
+        let types = DocumentTypes::new()
+            .with(DocumentType::new("Logs", [".evtc", ".zevtc"]))
+            .with(DocumentType::new("Archives", [".gz"]))
+            .with(DocumentType::new("Tarballs", [".tar.gz"]));
+
+        assert_eq!(classify(&types, "logs/20240101.zevtc"), Some("Logs"));
+        assert_eq!(classify(&types, "backup.evtc.gz"), Some("Archives"));
+        assert_eq!(classify(&types, "backup.tar.gz"), Some("Archives"));
+        assert_eq!(classify(&types, "20240101.ZEVTC"), None);
+        assert_eq!(classify(&types, "evtc"), None);
+        assert!(types.check().is_err());
+        assert!(DocumentTypes::new()
+            .with(DocumentType::new("Tarballs", [".tar.gz"]))
+            .check()
+            .is_err());
+        assert!(DocumentTypes::new()
+            .with(DocumentType::new("All", [".*"]))
+            .check()
+            .is_ok());
+    }
+
+    #[test]
+    fn classify_prefers_exact_extensions_then_the_first_type() {
+        let types = DocumentTypes::new()
+            .with(DocumentType::new("Anything", [".*"]))
+            .with(DocumentType::new("Logs", [".evtc"]))
+            .with(DocumentType::new("Raw logs", [".evtc", ".bin"]));
+
+        assert_eq!(classify(&types, "a.evtc"), Some("Logs"));
+        assert_eq!(classify(&types, "a.bin"), Some("Raw logs"));
+        assert_eq!(classify(&types, "a.txt"), Some("Anything"));
+        assert_eq!(classify(&types, "README"), Some("Anything"));
+    }
+
+    #[test]
+    fn extension_styles_expand_case_and_keep_the_first_type() {
+        let types = DocumentTypes::new()
+            .with(
+                DocumentType::new("Logs", [".Evtc"])
+                    .color([1.0, 0.0, 0.0, 1.0])
+                    .case_insensitive(),
+            )
+            .with(DocumentType::new("Raw logs", [".evtc", ".bin"]).icon("[raw]"))
+            .with(DocumentType::new("Anything", [".*"]));
+
+        let styles = types.extension_styles();
+        let logs = types.get("Logs").unwrap().style();
+        for extension in [".Evtc", ".evtc", ".EVTC"] {
+            assert_eq!(styles.get(extension), Some(&logs), "{}", extension);
+        }
+        assert_eq!(
+            styles.get(".bin"),
+            Some(&types.get("Raw logs").unwrap().style())
+        );
+        assert_eq!(styles.get(".*"), None);
+        assert_eq!(styles.len(), 4);
+        assert_eq!(classify(&types, "a.EVTC"), Some("Logs"));
+    }
+}
@@ -0,0 +1,69 @@
+//! Error type of the safe API.
+
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::FilterError;
+
+/// Errors reported by [`FileDialog`](crate::FileDialog) and its builders.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FileDialogError {
+    /// A string argument contains an interior NUL byte.
+    InvalidString {
+        /// Name of the offending argument, e.g. `"title"`.
+        field: &'static str,
+        /// Byte position of the NUL byte.
+        position: usize,
+    },
+    /// A path cannot be passed to ImGuiFileDialog without losing information.
+    InvalidPath(PathBuf),
+    /// A path returned by ImGuiFileDialog is not valid UTF-8.
+    ///
+    /// Only reported outside of Unix, where paths cannot hold arbitrary bytes.
+    NonUnicodePath(Vec<u8>),
+    /// The API was used in a way that makes no sense for the dialog.
+    Misuse(&'static str),
+    /// A [`FilterSet`](crate::FilterSet) does not render to a valid filter string.
+    InvalidFilter(FilterError),
+    /// Another dialog is open and the [`OpenPolicy`](crate::OpenPolicy) is `Reject`.
+    AlreadyOpen {
+        /// Key of the dialog that was not opened.
+        key: String,
+    },
+    /// Every name tried for a [`file_name_template`](crate::FileDialogBuilder::file_name_template)
+    /// already exists.
+    NoFreeFileName {
+        /// The template as given to the builder.
+        template: String,
+    },
+}
+
+impl fmt::Display for FileDialogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidString { field, position } => {
+                write!(f, "{} contains a NUL byte at position {}", field, position)
+            }
+            Self::InvalidPath(path) => {
+                write!(f, "path cannot be passed to the dialog: {}", path.display())
+            }
+            Self::NonUnicodePath(bytes) => write!(
+                f,
+                "path returned by the dialog is not valid UTF-8: {}",
+                String::from_utf8_lossy(bytes)
+            ),
+            Self::Misuse(reason) => f.write_str(reason),
+            Self::InvalidFilter(e) => write!(f, "invalid filters: {}", e),
+            Self::AlreadyOpen { key } => {
+                write!(f, "cannot open {:?} while another dialog is open", key)
+            }
+            Self::NoFreeFileName { template } => {
+                write!(f, "no free file name found for template {:?}", template)
+            }
+        }
+    }
+}
+
+impl Error for FileDialogError {}
@@ -0,0 +1,446 @@
+//! Typed file filters rendering to the ImGuiFileDialog filter syntax.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Characters with a meaning in the filter syntax.
+const RESERVED: [char; 4] = [',', '{', '}', '\0'];
+
+/// One entry of the filter combo box.
+///
+/// # Example
+///
+/// ```
+/// use imgui_filedialog::Filter;
+///
+/// assert_eq!(Filter::extension(".txt").to_string(), ".txt");
+/// assert_eq!(Filter::group("Images", [".png", ".jpg"]).to_string(), "Images{.png,.jpg}");
+/// assert_eq!(Filter::extension(".txt").case_insensitive().to_string(), ".txt{.txt,.TXT}");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Filter {
+    name: Option<String>,
+    extensions: Vec<String>,
+    case_insensitive: bool,
+}
+
+impl Filter {
+    /// A single extension, e.g. `".txt"`.
+    pub fn extension(extension: impl Into<String>) -> Self {
+        Self {
+            name: None,
+            extensions: vec![extension.into()],
+            case_insensitive: false,
+        }
+    }
+
+    /// A named group of extensions, e.g. `Filter::group("Images", [".png", ".jpg"])`.
+    pub fn group<I, S>(name: impl Into<String>, extensions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            name: Some(name.into()),
+            extensions: extensions.into_iter().map(Into::into).collect(),
+            case_insensitive: false,
+        }
+    }
+
+    /// The "all files" entry, `.*`.
+    pub fn all() -> Self {
+        Self::extension(".*")
+    }
+
+    /// Also match the lowercase and uppercase spelling of every extension.
+    ///
+    /// A single extension becomes a group named after it, since ImGuiFileDialog
+    /// only matches several spellings within a group.
+    pub fn case_insensitive(mut self) -> Self {
+        self.case_insensitive = true;
+        self
+    }
+
+    /// Get the name shown in the combo box and returned by
+    /// [`FileDialog::current_filter`](crate::FileDialog::current_filter).
+    pub fn name(&self) -> &str {
+        match &self.name {
+            Some(name) => name,
+            None => self.extensions.first().map(String::as_str).unwrap_or(""),
+        }
+    }
+
+    /// Get the extensions as given, before case expansion.
+    pub fn extensions(&self) -> &[String] {
+        &self.extensions
+    }
+
+    /// Check if [`case_insensitive`](Self::case_insensitive) was set.
+    pub fn is_case_insensitive(&self) -> bool {
+        self.case_insensitive
+    }
+
+    /// Check if this is the [`all`](Self::all) entry.
+    pub fn is_all(&self) -> bool {
+        self.name.is_none() && self.extensions == [".*"]
+    }
+
+    /// Get the extensions as written to the filter string.
+    ///
+    /// Spellings added by case expansion are skipped if already present.
+    pub(crate) fn spellings(&self) -> Vec<String> {
+        let mut out: Vec<String> = Vec::new();
+        for extension in &self.extensions {
+            out.push(extension.clone());
+            if self.case_insensitive {
+                for variant in [extension.to_lowercase(), extension.to_uppercase()] {
+                    if !out.contains(&variant) && !self.extensions.contains(&variant) {
+                        out.push(variant);
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Write this filter to `out`, returning the first problem found.
+    fn emit(&self, out: &mut String) -> Result<(), FilterError> {
+        let start = out.len();
+        let mut first_error = None;
+        let mut fail = |kind, position| {
+            first_error.get_or_insert(FilterError { kind, position });
+        };
+
+        if self.extensions.is_empty() && self.name.is_none() {
+            fail(FilterErrorKind::EmptyExtension, start);
+        }
+
+        let spellings = self.spellings();
+        let grouped = self.name.is_some() || spellings.len() > 1;
+        if grouped {
+            let name = self.name();
+            if name.is_empty() {
+                fail(FilterErrorKind::EmptyName, start);
+            }
+            if let Some((i, c)) = name.char_indices().find(|(_, c)| RESERVED.contains(c)) {
+                fail(FilterErrorKind::InvalidCharacter(c), start + i);
+            }
+            out.push_str(name);
+            out.push('{');
+            if spellings.is_empty() {
+                fail(FilterErrorKind::EmptyGroup, out.len());
+            }
+        }
+
+        for (i, extension) in spellings.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let position = out.len();
+            if let Err(kind) = check_extension(extension) {
+                fail(kind, position);
+            }
+            if spellings[..i].contains(extension) {
+                fail(FilterErrorKind::Duplicate(extension.clone()), position);
+            }
+            out.push_str(extension);
+        }
+
+        if grouped {
+            out.push('}');
+        }
+
+        first_error.map_or(Ok(()), Err)
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = String::new();
+        let _ = self.emit(&mut out);
+        f.write_str(&out)
+    }
+}
+
+/// Check a single extension, e.g. `.txt` or `.*`.
+fn check_extension(extension: &str) -> Result<(), FilterErrorKind> {
+    if extension.is_empty() {
+        return Err(FilterErrorKind::EmptyExtension);
+    }
+    if !extension.starts_with('.') {
+        return Err(FilterErrorKind::MissingDot);
+    }
+    if extension.len() == 1 {
+        return Err(FilterErrorKind::EmptyExtension);
+    }
+    if let Some(c) = extension.chars().find(|c| RESERVED.contains(c)) {
+        return Err(FilterErrorKind::InvalidCharacter(c));
+    }
+    Ok(())
+}
+
+/// The entries of the filter combo box, in order.
+///
+/// Renders to the string [`FileDialogBuilder::filters`](crate::FileDialogBuilder::filters)
+/// takes, and is checked when the dialog is built.
+///
+/// # Example
+///
+/// ```
+/// use imgui_filedialog::{Filter, FilterSet};
+///
+/// let filters = FilterSet::new()
+///     .with(Filter::group("Logs", [".log", ".evtc"]).case_insensitive())
+///     .with(Filter::extension(".zip"))
+///     .all_files();
+/// assert_eq!(filters.to_string(), "Logs{.log,.LOG,.evtc,.EVTC},.zip,.*");
+/// assert_eq!(filters.find(".zip"), Some(&Filter::extension(".zip")));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct FilterSet {
+    filters: Vec<Filter>,
+}
+
+impl FilterSet {
+    /// Create an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse a filter string, e.g. one read from a config file.
+    ///
+    /// This is the inverse of [`to_string`](ToString::to_string): a string that
+    /// parses renders back to exactly the same string. Case-expanded filters come
+    /// back as plain groups, e.g. `.txt{.txt,.TXT}` is a group named `.txt`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use imgui_filedialog::{FilterErrorKind, FilterSet};
+    ///
+    /// let filters = FilterSet::parse("Images{.png,.jpg},.*").unwrap();
+    /// assert_eq!(filters.len(), 2);
+    /// assert_eq!(filters.to_string(), "Images{.png,.jpg},.*");
+    ///
+    /// let error = FilterSet::parse("Images{.png,jpg}").unwrap_err();
+    /// assert_eq!(error.kind, FilterErrorKind::MissingDot);
+    /// assert_eq!(error.position, 12);
+    /// ```
+    pub fn parse(s: &str) -> Result<Self, FilterError> {
+        let unbalanced = |position| FilterError {
+            kind: FilterErrorKind::UnbalancedBrace,
+            position,
+        };
+
+        let mut set = Self::new();
+        let mut start = 0;
+        let mut open = None;
+        let mut close = None;
+        for (i, c) in s.char_indices() {
+            match c {
+                '{' if open.is_some() => return Err(unbalanced(i)),
+                '{' => open = Some(i),
+                '}' if open.is_none() || close.is_some() => return Err(unbalanced(i)),
+                '}' => close = Some(i),
+                ',' if open.is_some() && close.is_none() => {}
+                ',' => {
+                    set.push(parse_filter(&s[..i], start, open.zip(close)));
+                    start = i + 1;
+                    open = None;
+                    close = None;
+                }
+                c if close.is_some() => {
+                    return Err(FilterError {
+                        kind: FilterErrorKind::InvalidCharacter(c),
+                        position: i,
+                    })
+                }
+                _ => {}
+            }
+        }
+        if let (Some(open), None) = (open, close) {
+            return Err(unbalanced(open));
+        }
+        if !s.is_empty() {
+            set.push(parse_filter(s, start, open.zip(close)));
+        }
+
+        // The set renders back to `s`, so positions of problems found while
+        // rendering are positions in `s`.
+        set.validate()?;
+        Ok(set)
+    }
+
+    /// Add a filter.
+    pub fn with(mut self, filter: Filter) -> Self {
+        self.push(filter);
+        self
+    }
+
+    /// Add the "all files" entry.
+    pub fn all_files(self) -> Self {
+        self.with(Filter::all())
+    }
+
+    /// Add a filter.
+    pub fn push(&mut self, filter: Filter) {
+        self.filters.push(filter);
+    }
+
+    /// Get the filters in order.
+    pub fn filters(&self) -> &[Filter] {
+        &self.filters
+    }
+
+    /// Get the number of filters.
+    pub fn len(&self) -> usize {
+        self.filters.len()
+    }
+
+    /// Check if the set has no filters.
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+
+    /// Find the filter with the given name, as returned by
+    /// [`FileDialog::current_filter`](crate::FileDialog::current_filter).
+    pub fn find(&self, name: &str) -> Option<&Filter> {
+        self.filters.iter().find(|filter| filter.name() == name)
+    }
+
+    /// Move the filter with the given name to the front, keeping the order of
+    /// the others. Does nothing if there is no such filter.
+    pub(crate) fn move_to_front(&mut self, name: &str) {
+        if let Some(index) = self.filters.iter().position(|filter| filter.name() == name) {
+            self.filters[..=index].rotate_right(1);
+        }
+    }
+
+    /// Check that the set renders to a valid filter string.
+    ///
+    /// The position of an error is a byte offset into [`to_string`](ToString::to_string).
+    pub fn validate(&self) -> Result<(), FilterError> {
+        self.emit(&mut String::new())
+    }
+
+    fn emit(&self, out: &mut String) -> Result<(), FilterError> {
+        if self.filters.is_empty() {
+            return Err(FilterError {
+                kind: FilterErrorKind::Empty,
+                position: 0,
+            });
+        }
+
+        let mut first_error = None;
+        for (i, filter) in self.filters.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            let position = out.len();
+            if let Err(e) = filter.emit(out) {
+                first_error.get_or_insert(e);
+            }
+            if self.filters[..i]
+                .iter()
+                .any(|other| other.name() == filter.name())
+            {
+                first_error.get_or_insert(FilterError {
+                    kind: FilterErrorKind::Duplicate(filter.name().to_owned()),
+                    position,
+                });
+            }
+        }
+        first_error.map_or(Ok(()), Err)
+    }
+}
+
+/// Build the filter ending at the end of `s` and starting at `start`, with the
+/// positions of its braces if it is a group.
+fn parse_filter(s: &str, start: usize, braces: Option<(usize, usize)>) -> Filter {
+    match braces {
+        Some((open, close)) => {
+            let name = &s[start..open];
+            let extensions = &s[open + 1..close];
+            if extensions.is_empty() {
+                Filter::group(name, Vec::<String>::new())
+            } else {
+                Filter::group(name, extensions.split(','))
+            }
+        }
+        None => Filter::extension(&s[start..]),
+    }
+}
+
+impl FromStr for FilterSet {
+    type Err = FilterError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for FilterSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut out = String::new();
+        let _ = self.emit(&mut out);
+        f.write_str(&out)
+    }
+}
+
+impl FromIterator<Filter> for FilterSet {
+    fn from_iter<I: IntoIterator<Item = Filter>>(iter: I) -> Self {
+        Self {
+            filters: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// A problem in a filter string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterError {
+    /// What is wrong.
+    pub kind: FilterErrorKind,
+    /// Byte offset into the filter string.
+    pub position: usize,
+}
+
+/// Kinds of [`FilterError`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FilterErrorKind {
+    /// There are no filters.
+    Empty,
+    /// A group has no name.
+    EmptyName,
+    /// An extension is empty or only a `.`.
+    EmptyExtension,
+    /// A group has no extensions.
+    EmptyGroup,
+    /// An extension does not start with `.`.
+    MissingDot,
+    /// A name or extension contains a character reserved by the syntax.
+    InvalidCharacter(char),
+    /// A filter name or an extension in a group appears twice.
+    Duplicate(String),
+    /// A `{` without a matching `}`, or the other way around.
+    UnbalancedBrace,
+}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            FilterErrorKind::Empty => f.write_str("no filters")?,
+            FilterErrorKind::EmptyName => f.write_str("group has no name")?,
+            FilterErrorKind::EmptyExtension => f.write_str("empty extension")?,
+            FilterErrorKind::EmptyGroup => f.write_str("group has no extensions")?,
+            FilterErrorKind::MissingDot => f.write_str("extension does not start with '.'")?,
+            FilterErrorKind::InvalidCharacter(c) => write!(f, "unexpected {:?}", c)?,
+            FilterErrorKind::Duplicate(entry) => write!(f, "duplicate entry {:?}", entry)?,
+            FilterErrorKind::UnbalancedBrace => f.write_str("unbalanced brace")?,
+        }
+        write!(f, " at position {}", self.position)
+    }
+}
+
+impl Error for FilterError {}
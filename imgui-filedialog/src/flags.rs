@@ -0,0 +1,44 @@
+//! Typed ImGuiFileDialog flags.
+
+use crate::{sys, DialogMode, FileDialogError};
+
+bitflags::bitflags! {
+    /// Behavior flags of a file dialog.
+    ///
+    /// Set them with [`FileDialogBuilder::flags`](crate::FileDialogBuilder::flags)
+    /// or the dedicated builder methods.
+    #[derive(Default)]
+    pub struct DialogFlags: sys::ImGuiFileDialogFlags {
+        /// Ask for confirmation before overwriting an existing file (save dialogs only).
+        const CONFIRM_OVERWRITE = sys::ImGuiFileDialogFlags_ConfirmOverwrite;
+        /// Hide files and directories starting with a `.`.
+        const DONT_SHOW_HIDDEN_FILES = sys::ImGuiFileDialogFlags_DontShowHiddenFiles;
+        /// Remove the button for creating a new directory.
+        const DISABLE_CREATE_DIRECTORY_BUTTON =
+            sys::ImGuiFileDialogFlags_DisableCreateDirectoryButton;
+        /// Hide the file type column.
+        const HIDE_COLUMN_TYPE = sys::ImGuiFileDialogFlags_HideColumnType;
+        /// Hide the file size column.
+        const HIDE_COLUMN_SIZE = sys::ImGuiFileDialogFlags_HideColumnSize;
+        /// Hide the modification date column.
+        const HIDE_COLUMN_DATE = sys::ImGuiFileDialogFlags_HideColumnDate;
+    }
+}
+
+impl DialogFlags {
+    /// Check that the flags make sense for a dialog of the given mode.
+    ///
+    /// Rejects bits that are not one of the flags above, and
+    /// [`CONFIRM_OVERWRITE`](Self::CONFIRM_OVERWRITE) outside of save dialogs.
+    pub fn check(self, mode: DialogMode) -> Result<(), FileDialogError> {
+        if !Self::all().contains(self) {
+            return Err(FileDialogError::Misuse("unknown dialog flags"));
+        }
+        if self.contains(Self::CONFIRM_OVERWRITE) && mode != DialogMode::SaveFile {
+            return Err(FileDialogError::Misuse(
+                "confirm_overwrite only applies to save dialogs",
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+//! Dialog keys and handles.
+
+use std::borrow::Cow;
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+use crate::mode::{self, Mode};
+use crate::{c_string, FileDialog, FileDialogError};
+
+/// Handle to a dialog opened with [`FileDialogBuilder::build`](crate::FileDialogBuilder::build).
+///
+/// The handle owns the key as a C string, so displaying or polling the dialog
+/// through it does not allocate. Passing the handle around instead of repeating
+/// the key string also rules out typos between the open and display sites.
+///
+/// The [`mode`](crate::mode) parameter decides what [`FileDialog::poll`] returns
+/// for the handle: builders return handles typed by their mode, while handles
+/// created with [`new`](Self::new) return the raw [`Selection`](crate::Selection).
+pub struct DialogHandle<M = mode::Any> {
+    key: CString,
+    mode: PhantomData<fn() -> M>,
+}
+
+// Implemented by hand, as derives would require the mode to implement them too.
+impl<M> fmt::Debug for DialogHandle<M> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DialogHandle")
+            .field("key", &self.key)
+            .finish()
+    }
+}
+
+impl<M> Clone for DialogHandle<M> {
+    fn clone(&self) -> Self {
+        Self {
+            key: self.key.clone(),
+            mode: PhantomData,
+        }
+    }
+}
+
+impl<M> PartialEq for DialogHandle<M> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key
+    }
+}
+
+impl<M> Eq for DialogHandle<M> {}
+
+impl<M> Hash for DialogHandle<M> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.hash(state);
+    }
+}
+
+impl DialogHandle {
+    /// Create a handle for `key` without opening a dialog.
+    pub fn new(key: &str) -> Result<Self, FileDialogError> {
+        Ok(Self::from_c_key(c_string("key", key)?))
+    }
+
+    pub(crate) fn from_c_key(key: CString) -> Self {
+        Self {
+            key,
+            mode: PhantomData,
+        }
+    }
+}
+
+impl<M> DialogHandle<M> {
+    pub(crate) fn c_str(&self) -> &CStr {
+        &self.key
+    }
+
+    /// The key of the dialog.
+    pub fn key(&self) -> &str {
+        // Handles are only created from `&str`.
+        self.key.to_str().unwrap_or_default()
+    }
+
+    /// Drop the mode, e.g. to store handles of different modes together.
+    pub fn into_untyped(self) -> DialogHandle {
+        self.cast()
+    }
+
+    pub(crate) fn cast<N>(self) -> DialogHandle<N> {
+        DialogHandle {
+            key: self.key,
+            mode: PhantomData,
+        }
+    }
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Types that identify a dialog: `str`, `String` and [`DialogHandle`].
+pub trait DialogKey: private::Sealed {
+    /// What [`FileDialog::poll`] returns for a confirmed dialog under this key.
+    type Output;
+
+    /// The key as a C string.
+    #[doc(hidden)]
+    fn c_key(&self) -> Result<Cow<'_, CStr>, FileDialogError>;
+
+    /// Read the result of a confirmed dialog.
+    #[doc(hidden)]
+    fn output(dialog: &FileDialog) -> Result<Option<Self::Output>, FileDialogError>;
+}
+
+impl private::Sealed for str {}
+
+impl DialogKey for str {
+    type Output = <mode::Any as Mode>::Output;
+
+    fn c_key(&self) -> Result<Cow<'_, CStr>, FileDialogError> {
+        c_string("key", self).map(Cow::Owned)
+    }
+
+    fn output(dialog: &FileDialog) -> Result<Option<Self::Output>, FileDialogError> {
+        mode::Any::output(dialog)
+    }
+}
+
+impl private::Sealed for String {}
+
+impl DialogKey for String {
+    type Output = <mode::Any as Mode>::Output;
+
+    fn c_key(&self) -> Result<Cow<'_, CStr>, FileDialogError> {
+        self.as_str().c_key()
+    }
+
+    fn output(dialog: &FileDialog) -> Result<Option<Self::Output>, FileDialogError> {
+        mode::Any::output(dialog)
+    }
+}
+
+impl<M: Mode> private::Sealed for DialogHandle<M> {}
+
+impl<M: Mode> DialogKey for DialogHandle<M> {
+    type Output = M::Output;
+
+    fn c_key(&self) -> Result<Cow<'_, CStr>, FileDialogError> {
+        Ok(Cow::Borrowed(&self.key))
+    }
+
+    fn output(dialog: &FileDialog) -> Result<Option<Self::Output>, FileDialogError> {
+        M::output(dialog)
+    }
+}
@@ -8,43 +8,101 @@
 //! # Example
 //!
 //! ```no_run
-//! use imgui_filedialog::FileDialog;
+//! use imgui_filedialog::{DialogOutcome, FileDialog};
 //!
+//! # fn main() -> Result<(), imgui_filedialog::FileDialogError> {
 //! // Create a dialog instance (typically stored in your app state)
 //! let mut dialog = FileDialog::new();
 //!
 //! // Open the dialog (e.g., when a button is clicked)
-//! dialog.open_file()
+//! let handle = dialog.open_file()
 //!     .title("Select a File")
 //!     .filters(".txt,.md,.rs")
 //!     .path(".")
-//!     .build("choose_file");
+//!     .build("choose_file")?;
 //!
 //! // In your render loop:
-//! if dialog.display("choose_file", [400.0, 300.0], [800.0, 600.0]) {
-//!     if dialog.is_ok() {
-//!         if let Some(selection) = dialog.selection() {
-//!             for path in selection.files() {
-//!                 println!("Selected: {:?}", path);
-//!             }
-//!         }
+//! if let DialogOutcome::Accepted { value: paths, .. } =
+//!     dialog.poll(&handle, [400.0, 300.0], [800.0, 600.0])
+//! {
+//!     for path in paths {
+//!         println!("Selected: {:?}", path);
 //!     }
-//!     dialog.close();
 //! }
+//! # Ok(())
+//! # }
 //! ```
 
-use std::ffi::{CStr, CString};
-use std::os::raw::c_char;
-use std::path::PathBuf;
+use std::any::Any;
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::ffi::{CStr, CString, OsStr, OsString};
+use std::marker::PhantomData;
+use std::os::raw::{c_char, c_void};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::ptr::NonNull;
+use std::sync::mpsc::Sender;
+use std::time::SystemTime;
+
+use arcdps_imgui::{StyleColor, Ui};
 
 pub use imgui_filedialog_sys as sys;
 
+#[cfg(feature = "serde")]
+mod codec;
+mod delivery;
+mod display;
+mod documents;
+mod error;
+mod filter;
+mod flags;
+mod handle;
+mod manager;
+mod memory;
+pub mod mode;
+mod recent;
+mod save;
+mod styles;
+mod template;
+mod write;
+
+#[cfg(feature = "serde")]
+use codec::PendingDocument;
+#[cfg(feature = "serde")]
+pub use codec::{Codec, DocumentError, Position};
+use delivery::Completion;
+pub use delivery::{DialogEvent, DialogFuture};
+pub use display::DisplayOptions;
+pub use documents::{DocumentType, DocumentTypes};
+pub use error::FileDialogError;
+pub use filter::{Filter, FilterError, FilterErrorKind, FilterSet};
+pub use flags::DialogFlags;
+pub use handle::{DialogHandle, DialogKey};
+pub use manager::DialogManager;
+pub use memory::{DialogMemory, DialogState};
+use mode::{BuilderMode, FileMode, PathMode, SaveMode};
+pub use recent::{recent_files_menu, MruList};
+pub use save::{ExtensionPolicy, SaveTarget};
+pub use styles::{ExtensionStyle, ExtensionStyles, StyleChange};
+pub use write::WriteError;
+
 /// A file dialog context.
 ///
 /// This wraps the ImGuiFileDialog C++ class and manages its lifetime.
 /// Create one instance and reuse it for the lifetime of your application.
 pub struct FileDialog {
     ptr: *mut sys::ImGuiFileDialog,
+    /// Rust-side state of the open dialog, passed to ImGuiFileDialog as `user_datas`.
+    ///
+    /// Only set by [`OpenRequest::open`], and only freed once the C++ dialog is
+    /// closed or destroyed.
+    data: Option<NonNull<DialogData>>,
+    policy: OpenPolicy,
+    memory: DialogMemory,
+    recent: MruList,
+    /// Dialogs waiting for the open one to close, see [`OpenPolicy::Queue`].
+    queue: VecDeque<OpenRequest>,
 }
 
 impl Default for FileDialog {
@@ -57,56 +115,189 @@ impl FileDialog {
     /// Create a new file dialog context.
     pub fn new() -> Self {
         let ptr = unsafe { sys::IGFD_Create() };
-        Self { ptr }
+        Self {
+            ptr,
+            data: None,
+            policy: OpenPolicy::default(),
+            memory: DialogMemory::new(),
+            recent: MruList::new(),
+            queue: VecDeque::new(),
+        }
+    }
+
+    /// Set what [`FileDialogBuilder::build`] does while another dialog is open.
+    ///
+    /// Defaults to [`OpenPolicy::Replace`]. Builders can override it with
+    /// [`FileDialogBuilder::on_conflict`].
+    pub fn set_open_policy(&mut self, policy: OpenPolicy) {
+        self.policy = policy;
+    }
+
+    /// Get the current open-conflict policy.
+    pub fn open_policy(&self) -> OpenPolicy {
+        self.policy
+    }
+
+    /// Get the states remembered by dialogs opened with [`FileDialogBuilder::remember`].
+    pub fn memory(&self) -> &DialogMemory {
+        &self.memory
+    }
+
+    /// Get the remembered states for editing.
+    pub fn memory_mut(&mut self) -> &mut DialogMemory {
+        &mut self.memory
+    }
+
+    /// Replace the remembered states, e.g. with ones loaded from disk.
+    pub fn set_memory(&mut self, memory: DialogMemory) {
+        self.memory = memory;
+    }
+
+    /// Get the files and directories chosen in confirmed dialogs.
+    pub fn recent(&self) -> &MruList {
+        &self.recent
+    }
+
+    /// Get the recent files and directories for editing.
+    pub fn recent_mut(&mut self) -> &mut MruList {
+        &mut self.recent
+    }
+
+    /// Replace the recent files and directories.
+    pub fn set_recent(&mut self, recent: MruList) {
+        self.recent = recent;
+    }
+
+    /// Number of dialogs queued by [`OpenPolicy::Queue`].
+    pub fn queued(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Drop all queued dialogs without opening them.
+    pub fn clear_queue(&mut self) {
+        self.queue.clear();
     }
 
     /// Open a file selection dialog.
     ///
     /// Returns a builder to configure the dialog before opening.
-    pub fn open_file(&mut self) -> FileDialogBuilder<'_> {
-        FileDialogBuilder::new(self, DialogMode::OpenFile)
+    pub fn open_file(&mut self) -> OpenFileBuilder<'_> {
+        FileDialogBuilder::new(BuilderTarget::Dialog(self))
     }
 
     /// Open a directory selection dialog.
     ///
     /// Returns a builder to configure the dialog before opening.
-    pub fn open_directory(&mut self) -> FileDialogBuilder<'_> {
-        FileDialogBuilder::new(self, DialogMode::OpenDirectory)
+    pub fn open_directory(&mut self) -> OpenDirectoryBuilder<'_> {
+        FileDialogBuilder::new(BuilderTarget::Dialog(self))
     }
 
     /// Open a save file dialog.
     ///
     /// Returns a builder to configure the dialog before opening.
-    pub fn save_file(&mut self) -> FileDialogBuilder<'_> {
-        FileDialogBuilder::new(self, DialogMode::SaveFile)
+    pub fn save_file(&mut self) -> SaveFileBuilder<'_> {
+        FileDialogBuilder::new(BuilderTarget::Dialog(self))
     }
 
     /// Display the dialog.
     ///
     /// Call this every frame in your render loop.
-    /// Returns `true` while the dialog is visible (not yet closed).
+    /// Returns `true` once the user has confirmed or cancelled the dialog, and
+    /// `false` while it is still open or not open under `key`. Read the result,
+    /// e.g. with [`is_ok`](Self::is_ok), then [`close`](Self::close) the dialog.
+    /// A key containing a NUL byte never matches an open dialog.
+    ///
+    /// Dialogs whose result is delivered elsewhere, e.g. with
+    /// [`on_complete`](FileDialogBuilder::on_complete), are closed as soon as they
+    /// finish and return `false`.
     ///
     /// # Arguments
     /// * `key` - The same key used when opening the dialog
     /// * `min_size` - Minimum dialog size `[width, height]`
     /// * `max_size` - Maximum dialog size `[width, height]`
-    pub fn display(&mut self, key: &str, min_size: [f32; 2], max_size: [f32; 2]) -> bool {
-        let key_c = CString::new(key).unwrap();
-        unsafe {
-            sys::IGFD_DisplayDialog(
-                self.ptr,
-                key_c.as_ptr(),
-                0,
-                sys::ImVec2 {
-                    x: min_size[0],
-                    y: min_size[1],
-                },
-                sys::ImVec2 {
-                    x: max_size[0],
-                    y: max_size[1],
-                },
-            )
-        }
+    pub fn display<K: DialogKey + ?Sized>(
+        &mut self,
+        key: &K,
+        min_size: [f32; 2],
+        max_size: [f32; 2],
+    ) -> bool {
+        let options = DisplayOptions::new().min_size(min_size).max_size(max_size);
+        self.display_impl(None, key, &options) == Shown::Finished
+    }
+
+    /// Display the dialog, making `ui` available to its side pane.
+    ///
+    /// Behaves like [`display`](Self::display). Dialogs opened with
+    /// [`FileDialogBuilder::side_pane`] must be displayed through this method
+    /// or [`display_with`](Self::display_with), otherwise the pane is left empty.
+    pub fn display_with_ui<K: DialogKey + ?Sized>(
+        &mut self,
+        ui: &Ui,
+        key: &K,
+        min_size: [f32; 2],
+        max_size: [f32; 2],
+    ) -> bool {
+        let options = DisplayOptions::new().min_size(min_size).max_size(max_size);
+        self.display_impl(Some(ui), key, &options) == Shown::Finished
+    }
+
+    /// Display the dialog with custom window options.
+    ///
+    /// Behaves like [`display_with_ui`](Self::display_with_ui), but also applies the
+    /// window flags, size constraints and positioning of `options`.
+    pub fn display_with<K: DialogKey + ?Sized>(
+        &mut self,
+        ui: &Ui,
+        key: &K,
+        options: &DisplayOptions,
+    ) -> bool {
+        self.display_impl(Some(ui), key, options) == Shown::Finished
+    }
+
+    /// Display the dialog and report whether the user has finished with it.
+    ///
+    /// Call this every frame instead of [`display`](Self::display). Once the user
+    /// confirms or cancels, the dialog is closed and the outcome is returned.
+    /// Dialogs whose result is delivered elsewhere, e.g. to a [`DialogFuture`] or
+    /// [`on_complete`](FileDialogBuilder::on_complete), always report
+    /// [`DialogOutcome::Pending`].
+    ///
+    /// Polling with the [`DialogHandle`] returned by a builder yields the result
+    /// type of its mode, e.g. a [`SaveTarget`] for [`save_file`](Self::save_file).
+    /// Plain string keys yield the raw [`Selection`].
+    ///
+    /// Like [`display`](Self::display), this has no [`Ui`] to hand to a
+    /// [`side_pane`](FileDialogBuilder::side_pane) or
+    /// [`validate`](FileDialogBuilder::validate) message, which are left empty.
+    /// Use [`poll_with`](Self::poll_with) for such dialogs.
+    ///
+    /// # Arguments
+    /// * `key` - The same key used when opening the dialog
+    /// * `min_size` - Minimum dialog size `[width, height]`
+    /// * `max_size` - Maximum dialog size `[width, height]`
+    pub fn poll<K: DialogKey + ?Sized>(
+        &mut self,
+        key: &K,
+        min_size: [f32; 2],
+        max_size: [f32; 2],
+    ) -> DialogOutcome<K::Output> {
+        let options = DisplayOptions::new().min_size(min_size).max_size(max_size);
+        let shown = self.display_impl(None, key, &options);
+        self.finish::<K>(shown)
+    }
+
+    /// Display the dialog with custom window options and report whether the user
+    /// has finished with it.
+    ///
+    /// See [`poll`](Self::poll) and [`display_with`](Self::display_with).
+    pub fn poll_with<K: DialogKey + ?Sized>(
+        &mut self,
+        ui: &Ui,
+        key: &K,
+        options: &DisplayOptions,
+    ) -> DialogOutcome<K::Output> {
+        let shown = self.display_impl(Some(ui), key, options);
+        self.finish::<K>(shown)
     }
 
     /// Returns `true` if the user clicked OK (confirmed selection).
@@ -120,59 +311,84 @@ impl FileDialog {
     }
 
     /// Check if a specific dialog key is open.
-    pub fn is_key_opened(&self, key: &str) -> bool {
-        let key_c = CString::new(key).unwrap();
+    pub fn is_key_opened<K: DialogKey + ?Sized>(&self, key: &K) -> bool {
+        let Ok(key_c) = key.c_key() else {
+            return false;
+        };
         unsafe { sys::IGFD_IsKeyOpened(self.ptr, key_c.as_ptr()) }
     }
 
     /// Close the dialog.
+    ///
+    /// This also releases the side pane closure and user data of the dialog, if any,
+    /// and opens the next queued dialog.
     pub fn close(&mut self) {
-        unsafe { sys::IGFD_CloseDialog(self.ptr) }
+        self.record_result();
+        self.close_current();
+
+        if let Some(next) = self.queue.pop_front() {
+            next.open(self);
+        }
     }
 
     /// Get the selected files.
     ///
-    /// Returns `None` if the dialog was cancelled or no files were selected.
-    pub fn selection(&self) -> Option<Selection> {
+    /// Returns `None` if the dialog was cancelled, and
+    /// [`FileDialogError::NonUnicodePath`] if a path cannot be represented.
+    pub fn selection(&self) -> Result<Option<Selection>, FileDialogError> {
         if !self.is_ok() {
-            return None;
+            return Ok(None);
         }
 
         let selection = unsafe { sys::IGFD_GetSelection(self.ptr) };
 
-        Some(Selection::new(selection))
+        Selection::new(selection, self.selected_filter().cloned()).map(Some)
     }
 
     /// Get the full file path (for save dialogs).
     ///
-    /// Returns `None` if the dialog was cancelled.
-    pub fn file_path_name(&self) -> Option<PathBuf> {
+    /// Returns `None` if the dialog was cancelled, and
+    /// [`FileDialogError::NonUnicodePath`] if the path cannot be represented.
+    pub fn file_path_name(&self) -> Result<Option<PathBuf>, FileDialogError> {
         if !self.is_ok() {
-            return None;
+            return Ok(None);
         }
 
-        unsafe {
-            let ptr = sys::IGFD_GetFilePathName(self.ptr);
-            if ptr.is_null() {
-                return None;
-            }
-            let path = ptr_to_pathbuf(ptr);
-            libc::free(ptr as *mut _);
-            Some(path)
+        unsafe { take_path(sys::IGFD_GetFilePathName(self.ptr)) }
+    }
+
+    /// Get the handle of the open dialog.
+    ///
+    /// Returns `None` if no dialog was opened through a builder or it has been closed.
+    pub fn opened_handle(&self) -> Option<&DialogHandle> {
+        self.data.map(|data| unsafe { &(*data.as_ptr()).handle })
+    }
+
+    /// Get the mode of the open dialog, if it was opened through a builder.
+    pub(crate) fn opened_mode(&self) -> Option<DialogMode> {
+        self.data.map(|data| unsafe { (*data.as_ptr()).mode })
+    }
+
+    /// Get the user data attached with [`FileDialogBuilder::user_data`].
+    ///
+    /// Returns `None` if no dialog is open, no user data was attached, or the
+    /// attached value is not a `T`.
+    pub fn user_data<T: Any>(&self) -> Option<&T> {
+        let data = self.data?;
+        let ptr = unsafe { sys::IGFD_GetUserDatas(self.ptr) };
+        if ptr != data.as_ptr() as *mut c_void {
+            return None;
         }
+
+        let data = unsafe { &*data.as_ptr() };
+        data.user_data.as_ref()?.downcast_ref()
     }
 
     /// Get the current directory path.
-    pub fn current_path(&self) -> Option<PathBuf> {
-        unsafe {
-            let ptr = sys::IGFD_GetCurrentPath(self.ptr);
-            if ptr.is_null() {
-                return None;
-            }
-            let path = ptr_to_pathbuf(ptr);
-            libc::free(ptr as *mut _);
-            Some(path)
-        }
+    ///
+    /// Fails with [`FileDialogError::NonUnicodePath`] if the path cannot be represented.
+    pub fn current_path(&self) -> Result<Option<PathBuf>, FileDialogError> {
+        unsafe { take_path(sys::IGFD_GetCurrentPath(self.ptr)) }
     }
 
     /// Get the current filter.
@@ -188,15 +404,32 @@ impl FileDialog {
         }
     }
 
+    /// Get the filter selected in the open dialog.
+    ///
+    /// Returns `None` if no dialog is open, or if it was built with a
+    /// [`filters`](FileDialogBuilder::filters) string that [`FilterSet::parse`]
+    /// rejects. Once the dialog is closed, e.g. by [`poll`](Self::poll), the
+    /// filter is available from [`Selection::filter`] and
+    /// [`SaveTarget::selected_filter`].
+    pub fn selected_filter(&self) -> Option<&Filter> {
+        let data = unsafe { &*self.data?.as_ptr() };
+        data.filter_set.as_ref()?.find(&self.current_filter()?)
+    }
+
     /// Set a custom file extension style.
     ///
     /// # Arguments
     /// * `extension` - Extension filter (e.g., ".txt", ".rs")
     /// * `color` - RGBA color `[r, g, b, a]`
     /// * `icon` - Optional icon/text prefix
-    pub fn set_extension_infos(&mut self, extension: &str, color: [f32; 4], icon: Option<&str>) {
-        let ext_c = CString::new(extension).unwrap();
-        let icon_c = icon.map(|s| CString::new(s).unwrap());
+    pub fn set_extension_infos(
+        &mut self,
+        extension: &str,
+        color: [f32; 4],
+        icon: Option<&str>,
+    ) -> Result<(), FileDialogError> {
+        let ext_c = c_string("extension", extension)?;
+        let icon_c = icon.map(|s| c_string("icon", s)).transpose()?;
         let icon_ptr = icon_c
             .as_ref()
             .map(|c| c.as_ptr())
@@ -215,6 +448,51 @@ impl FileDialog {
                 icon_ptr,
             );
         }
+        Ok(())
+    }
+
+    /// Set the style of an extension.
+    ///
+    /// Like [`set_extension_infos`](Self::set_extension_infos), taking an [`ExtensionStyle`].
+    pub fn set_extension_style(
+        &mut self,
+        extension: &str,
+        style: &ExtensionStyle,
+    ) -> Result<(), FileDialogError> {
+        self.set_extension_infos(extension, style.color, style.icon.as_deref())
+    }
+
+    /// Get the style currently set for an extension.
+    ///
+    /// Returns `None` if the extension has no style.
+    pub fn extension_style(&self, extension: &str) -> Option<ExtensionStyle> {
+        let ext_c = CString::new(extension).ok()?;
+        let mut color = sys::ImVec4 {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+            w: 0.0,
+        };
+        let mut icon_ptr: *mut c_char = std::ptr::null_mut();
+
+        unsafe {
+            let found =
+                sys::IGFD_GetExtentionInfos(self.ptr, ext_c.as_ptr(), &mut color, &mut icon_ptr);
+
+            // The icon is only allocated when the style has one, and is ours to free.
+            let icon = if icon_ptr.is_null() {
+                None
+            } else {
+                let icon = ptr_to_string(icon_ptr);
+                libc::free(icon_ptr as *mut _);
+                Some(icon)
+            };
+
+            found.then_some(ExtensionStyle {
+                color: [color.x, color.y, color.z, color.w],
+                icon,
+            })
+        }
     }
 
     /// Clear all extension settings.
@@ -229,11 +507,183 @@ impl FileDialog {
     pub fn as_ptr(&self) -> *mut sys::ImGuiFileDialog {
         self.ptr
     }
+
+    fn display_impl<K: DialogKey + ?Sized>(
+        &mut self,
+        ui: Option<&Ui>,
+        key: &K,
+        options: &DisplayOptions,
+    ) -> Shown {
+        let Ok(key_c) = key.c_key() else {
+            return Shown::Hidden;
+        };
+        // Displaying another key must not touch the dialog that is open.
+        if !unsafe { sys::IGFD_IsKeyOpened(self.ptr, key_c.as_ptr()) } {
+            return Shown::Hidden;
+        }
+
+        if self.take_completion_if(Completion::is_abandoned).is_some() {
+            self.close();
+            return Shown::Hidden;
+        }
+
+        if let (Some(data), Some(ui)) = (self.data, ui) {
+            unsafe { (*data.as_ptr()).ui = (ui as *const Ui).cast() };
+        }
+
+        let (min_size, max_size) = options.size_constraints();
+        // Only now that the dialog is known to be drawn: ImGui keeps
+        // SetNextWindow* state until the next window begins.
+        options.apply_next_window();
+        // ImGuiFileDialog returns true once the user confirmed or cancelled.
+        let finished = unsafe {
+            sys::IGFD_DisplayDialog(
+                self.ptr,
+                key_c.as_ptr(),
+                options.window_flags(),
+                min_size,
+                max_size,
+            )
+        };
+
+        if let Some(data) = self.data {
+            unsafe { (*data.as_ptr()).ui = std::ptr::null() };
+        }
+
+        if !finished {
+            return Shown::Hidden;
+        }
+        match self.take_completion_if(|_| true) {
+            Some(completion) => {
+                let handle = self
+                    .opened_handle()
+                    .cloned()
+                    .unwrap_or_else(|| DialogHandle::from_c_key(key_c.into_owned()));
+                let outcome = self.take_outcome::<DialogHandle<mode::Paths>>();
+                completion.complete(&handle, outcome);
+                Shown::Delivered
+            }
+            None => Shown::Finished,
+        }
+    }
+
+    /// Record a confirmed dialog in the recent list, and its state in the memory
+    /// if it was opened with [`FileDialogBuilder::remember`].
+    fn record_result(&mut self) {
+        let Some(data) = self.data else {
+            return;
+        };
+        let data = unsafe { &*data.as_ptr() };
+        if !self.is_ok() {
+            return;
+        }
+
+        // Paths that cannot be represented were already reported by the outcome.
+        let selection = self.selection().ok().flatten();
+        let file_path_name = self.file_path_name().ok().flatten();
+        match data.mode {
+            DialogMode::OpenFile => match &selection {
+                Some(selection) if selection.count() > 0 => self.recent.add_selection(selection),
+                _ => self
+                    .recent
+                    .add_files(file_path_name.iter().cloned().collect()),
+            },
+            DialogMode::SaveFile => {
+                self.recent
+                    .add_files(file_path_name.iter().cloned().collect());
+            }
+            DialogMode::OpenDirectory => {
+                if let Some(directory) = &file_path_name {
+                    self.recent.add_directory(directory);
+                }
+            }
+        }
+        if !data.remember {
+            return;
+        }
+
+        let file = match data.mode {
+            DialogMode::OpenFile => selection
+                .and_then(|selection| selection.get(0).map(|entry| entry.path.to_path_buf()))
+                .or(file_path_name),
+            DialogMode::SaveFile => file_path_name,
+            DialogMode::OpenDirectory => None,
+        };
+        let state = DialogState {
+            directory: self.current_path().ok().flatten(),
+            filter: match data.mode {
+                DialogMode::OpenDirectory => None,
+                _ => self.current_filter(),
+            },
+            file,
+        };
+        self.memory.insert(data.handle.key(), state);
+    }
+
+    /// Turn the result of a display call into an outcome, closing finished dialogs.
+    fn finish<K: DialogKey + ?Sized>(&mut self, shown: Shown) -> DialogOutcome<K::Output> {
+        match shown {
+            Shown::Finished => self.take_outcome::<K>(),
+            Shown::Hidden | Shown::Delivered => DialogOutcome::Pending,
+        }
+    }
+
+    /// Collect the outcome of a finished dialog, as the result type of `K`, and close it.
+    fn take_outcome<K: DialogKey + ?Sized>(&mut self) -> DialogOutcome<K::Output> {
+        let outcome = match K::output(self) {
+            Ok(Some(value)) => DialogOutcome::Accepted {
+                value,
+                user_data: self
+                    .data
+                    .and_then(|data| unsafe { (*data.as_ptr()).user_data.take() }),
+            },
+            Ok(None) => DialogOutcome::Cancelled,
+            Err(e) => DialogOutcome::Failed(e),
+        };
+        self.close();
+        outcome
+    }
+
+    /// Take the completion of the open dialog if it matches `predicate`.
+    fn take_completion_if(
+        &mut self,
+        predicate: impl FnOnce(&Completion) -> bool,
+    ) -> Option<Completion> {
+        let data = unsafe { &mut *self.data?.as_ptr() };
+        if predicate(data.completion.as_ref()?) {
+            data.completion.take()
+        } else {
+            None
+        }
+    }
+
+    /// Close the dialog and free its state, without recording its result or
+    /// opening a queued dialog.
+    fn close_current(&mut self) {
+        unsafe {
+            sys::IGFD_CloseDialog(self.ptr);
+            self.free_data();
+        }
+    }
+
+    /// Free the state of the dialog.
+    ///
+    /// # Safety
+    /// ImGuiFileDialog must no longer refer to the state, i.e. the dialog has
+    /// been closed or destroyed.
+    unsafe fn free_data(&mut self) {
+        if let Some(data) = self.data.take() {
+            drop(Box::from_raw(data.as_ptr()));
+        }
+    }
 }
 
 impl Drop for FileDialog {
     fn drop(&mut self) {
-        unsafe { sys::IGFD_Destroy(self.ptr) }
+        unsafe {
+            sys::IGFD_Destroy(self.ptr);
+            self.free_data();
+        }
     }
 }
 
@@ -241,19 +691,98 @@ impl Drop for FileDialog {
 // as long as it's not accessed concurrently.
 unsafe impl Send for FileDialog {}
 
+/// What happened to a dialog during one display call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Shown {
+    /// The dialog is not open under the key, or still waiting for the user.
+    Hidden,
+    /// The user confirmed or cancelled the dialog.
+    Finished,
+    /// The dialog finished and its result went to its [`Completion`].
+    Delivered,
+}
+
+/// Outcome of a dialog polled with [`FileDialog::poll`].
+///
+/// `T` is the [`DialogKey::Output`] of the polled key.
+#[must_use]
+pub enum DialogOutcome<T = Selection> {
+    /// The dialog is still open, or is not open under the polled key.
+    Pending,
+    /// The user confirmed the selection.
+    Accepted {
+        /// The result of the dialog.
+        value: T,
+        /// The value attached with [`FileDialogBuilder::user_data`], handed back
+        /// as the dialog is closed.
+        user_data: Option<Box<dyn Any + Send>>,
+    },
+    /// The user cancelled the dialog.
+    Cancelled,
+    /// The user confirmed the dialog, but its result cannot be represented,
+    /// see [`FileDialogError::NonUnicodePath`].
+    Failed(FileDialogError),
+}
+
+impl<T> DialogOutcome<T> {
+    /// Get the user data of an accepted dialog.
+    ///
+    /// Returns `None` unless the dialog was accepted and its user data is a `U`.
+    pub fn user_data<U: Any>(&self) -> Option<&U> {
+        match self {
+            Self::Accepted {
+                user_data: Some(data),
+                ..
+            } => data.downcast_ref(),
+            _ => None,
+        }
+    }
+}
+
 /// Builder for configuring a file dialog before opening.
-pub struct FileDialogBuilder<'a> {
-    dialog: &'a mut FileDialog,
-    mode: DialogMode,
-    title: Option<CString>,
-    filters: Option<CString>,
-    path: Option<CString>,
-    file_name: Option<CString>,
+///
+/// The [`mode`] parameter limits the options to those that apply to the dialog,
+/// see [`OpenFileBuilder`], [`SaveFileBuilder`] and [`OpenDirectoryBuilder`].
+pub struct FileDialogBuilder<'a, M> {
+    target: BuilderTarget<'a>,
+    mode: PhantomData<M>,
+    title: Option<String>,
+    filters: Option<String>,
+    filter_set: Option<FilterSet>,
+    extension_policy: ExtensionPolicy,
+    path: Option<PathBuf>,
+    file_name: Option<OsString>,
+    file_name_template: Option<String>,
     max_selection: i32,
     modal: bool,
-    flags: sys::ImGuiFileDialogFlags,
+    flags: DialogFlags,
+    pane: Option<SidePane>,
+    user_data: Option<Box<dyn Any + Send>>,
+    policy: Option<OpenPolicy>,
+    completion: Option<Completion>,
+    remember: bool,
+    validator: Option<ValidateFn>,
+    #[cfg(feature = "serde")]
+    document: Option<PendingDocument>,
 }
 
+/// Builder returned by [`FileDialog::open_file`].
+pub type OpenFileBuilder<'a> = FileDialogBuilder<'a, mode::OpenFile>;
+
+/// Builder returned by [`FileDialog::save_file`].
+pub type SaveFileBuilder<'a> = FileDialogBuilder<'a, mode::SaveFile>;
+
+/// Builder returned by [`FileDialog::open_directory`].
+pub type OpenDirectoryBuilder<'a> = FileDialogBuilder<'a, mode::OpenDirectory>;
+
+/// Builder returned by [`FileDialog::open_document`].
+#[cfg(feature = "serde")]
+pub type OpenDocumentBuilder<'a, T> = FileDialogBuilder<'a, mode::OpenDocument<T>>;
+
+/// Builder returned by [`FileDialog::save_document`].
+#[cfg(feature = "serde")]
+pub type SaveDocumentBuilder<'a> = FileDialogBuilder<'a, mode::SaveDocument>;
+
 /// Dialog mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DialogMode {
@@ -262,47 +791,409 @@ pub enum DialogMode {
     SaveFile,
 }
 
-impl<'a> FileDialogBuilder<'a> {
-    fn new(dialog: &'a mut FileDialog, mode: DialogMode) -> Self {
+/// What [`FileDialogBuilder::build`] does when a dialog with another key is already open.
+///
+/// A [`FileDialog`] shows one dialog at a time. Building a dialog under the key
+/// that is already open replaces it under every policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OpenPolicy {
+    /// Close the open dialog and show the new one.
+    #[default]
+    Replace,
+    /// Keep the open dialog and fail with [`FileDialogError::AlreadyOpen`].
+    Reject,
+    /// Show the new dialog once the open one is closed.
+    Queue,
+}
+
+/// Where a builder opens its dialog.
+pub(crate) enum BuilderTarget<'a> {
+    Dialog(&'a mut FileDialog),
+    /// An instance of the manager, picked by key once the dialog is built.
+    Manager(&'a mut DialogManager),
+}
+
+impl<'a, M: BuilderMode> FileDialogBuilder<'a, M> {
+    pub(crate) fn new(target: BuilderTarget<'a>) -> Self {
         Self {
-            dialog,
-            mode,
+            target,
+            mode: PhantomData,
             title: None,
             filters: None,
+            filter_set: None,
+            extension_policy: ExtensionPolicy::default(),
             path: None,
             file_name: None,
+            file_name_template: None,
             max_selection: 1,
             modal: false,
-            flags: sys::ImGuiFileDialogFlags_None,
+            flags: DialogFlags::empty(),
+            pane: None,
+            user_data: None,
+            policy: None,
+            completion: None,
+            remember: false,
+            validator: None,
+            #[cfg(feature = "serde")]
+            document: None,
         }
     }
 
     /// Set the dialog title.
     pub fn title(mut self, title: &str) -> Self {
-        self.title = Some(CString::new(title).unwrap());
+        self.title = Some(title.to_owned());
         self
     }
 
+    /// Set the initial directory path.
+    pub fn path(mut self, path: impl AsRef<Path>) -> Self {
+        self.path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Make this a modal dialog.
+    pub fn modal(mut self) -> Self {
+        self.modal = true;
+        self
+    }
+
+    /// Add dialog flags.
+    pub fn flags(mut self, flags: DialogFlags) -> Self {
+        self.flags |= flags;
+        self
+    }
+
+    /// Hide files and directories starting with a `.`.
+    pub fn hide_hidden_files(self) -> Self {
+        self.flags(DialogFlags::DONT_SHOW_HIDDEN_FILES)
+    }
+
+    /// Remove the button for creating a new directory.
+    pub fn disable_create_directory(self) -> Self {
+        self.flags(DialogFlags::DISABLE_CREATE_DIRECTORY_BUTTON)
+    }
+
+    /// Hide the file type column.
+    pub fn hide_type_column(self) -> Self {
+        self.flags(DialogFlags::HIDE_COLUMN_TYPE)
+    }
+
+    /// Hide the file size column.
+    pub fn hide_size_column(self) -> Self {
+        self.flags(DialogFlags::HIDE_COLUMN_SIZE)
+    }
+
+    /// Hide the modification date column.
+    pub fn hide_date_column(self) -> Self {
+        self.flags(DialogFlags::HIDE_COLUMN_DATE)
+    }
+
+    /// Draw a custom side pane next to the file list.
+    ///
+    /// The closure is called every frame while the dialog is displayed through
+    /// [`FileDialog::display_with_ui`], [`FileDialog::display_with`] or
+    /// [`FileDialog::poll_with`], and is dropped when the dialog is closed.
+    ///
+    /// # Arguments
+    /// * `width` - Width of the pane in pixels
+    /// * `pane` - Closure drawing the pane contents
+    pub fn side_pane<F>(mut self, width: f32, pane: F) -> Self
+    where
+        F: FnMut(&mut PaneContext<'_>) + Send + 'static,
+    {
+        self.pane = Some(SidePane {
+            width,
+            draw: Box::new(pane),
+        });
+        self
+    }
+
+    /// Check the selection every frame before the user can confirm it.
+    ///
+    /// The closure receives the selected files, or the typed file path when no
+    /// file is selected in the list, and is not called while nothing is chosen. While it
+    /// returns an error, the OK button is hidden and the message is shown in
+    /// the side pane. Without a [`side_pane`](Self::side_pane), a narrow one is
+    /// added for the message.
+    ///
+    /// The message is only drawn when the dialog is displayed through
+    /// [`FileDialog::display_with_ui`], [`FileDialog::display_with`] or
+    /// [`FileDialog::poll_with`].
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), imgui_filedialog::FileDialogError> {
+    /// # let mut dialog = imgui_filedialog::FileDialog::new();
+    /// dialog
+    ///     .open_file()
+    ///     .filters(".zevtc")
+    ///     .validate(|paths| match std::fs::metadata(&paths[0]) {
+    ///         Ok(meta) if meta.len() > 0 => Ok(()),
+    ///         Ok(_) => Err("the log is empty".to_owned()),
+    ///         Err(e) => Err(e.to_string()),
+    ///     })
+    ///     .build("open_log")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn validate<F>(mut self, validator: F) -> Self
+    where
+        F: FnMut(&[PathBuf]) -> Result<(), String> + Send + 'static,
+    {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+
+    /// Attach a value to the dialog.
+    ///
+    /// The dialog owns the value until it is closed. Retrieve it with
+    /// [`FileDialog::user_data`] while the dialog is open, e.g. to tell which
+    /// document the dialog was opened for. An accepted dialog hands it back in
+    /// [`DialogOutcome::Accepted`] and [`DialogEvent::Accepted`].
+    pub fn user_data<T: Any + Send>(mut self, value: T) -> Self {
+        self.user_data = Some(Box::new(value));
+        self
+    }
+
+    /// Remember the directory, filter and file of this dialog under its key.
+    ///
+    /// When the dialog is confirmed, its state is recorded in [`FileDialog::memory`].
+    /// The next dialog built with the same key and `remember()` starts from there:
+    /// the remembered file is preselected, and the remembered filter is moved to
+    /// the front of the filters so it is selected. An explicit [`path`](Self::path)
+    /// or [`file_name`](Self::file_name) takes precedence. Filters given as a
+    /// string that [`FilterSet::parse`] rejects are left as they are.
+    pub fn remember(mut self) -> Self {
+        self.remember = true;
+        self
+    }
+
+    /// Override the open-conflict policy of the [`FileDialog`] for this dialog.
+    pub fn on_conflict(mut self, policy: OpenPolicy) -> Self {
+        self.policy = Some(policy);
+        self
+    }
+
+    /// Open the dialog with the configured options.
+    ///
+    /// Fails without opening the dialog if an argument contains a NUL byte
+    /// or the options are inconsistent.
+    ///
+    /// Returns a [`DialogHandle`] to display and poll the dialog with, typed by
+    /// the mode of the builder.
+    ///
+    /// # Arguments
+    /// * `key` - Unique key to identify this dialog instance, or the handle of a
+    ///   previously opened dialog
+    pub fn build<K: DialogKey + ?Sized>(
+        mut self,
+        key: &K,
+    ) -> Result<DialogHandle<M>, FileDialogError> {
+        if self.max_selection < 0 {
+            return Err(FileDialogError::Misuse(
+                "multi_select count must not be negative",
+            ));
+        }
+        self.flags.check(M::MODE)?;
+        if self.validator.is_some() && self.pane.is_none() {
+            self.pane = Some(SidePane {
+                width: VALIDATION_PANE_WIDTH,
+                draw: Box::new(|_| {}),
+            });
+        }
+        if let Some(pane) = &self.pane {
+            if !(pane.width.is_finite() && pane.width > 0.0) {
+                return Err(FileDialogError::Misuse(
+                    "side pane width must be a positive number",
+                ));
+            }
+        }
+
+        let key_c = key.c_key()?;
+
+        let default_title = match M::MODE {
+            DialogMode::OpenFile => "Open File",
+            DialogMode::OpenDirectory => "Select Directory",
+            DialogMode::SaveFile => "Save File",
+        };
+        let title = c_string("title", self.title.as_deref().unwrap_or(default_title))?;
+
+        let handle = DialogHandle::from_c_key(key_c.into_owned());
+        let dialog = match self.target {
+            BuilderTarget::Dialog(dialog) => dialog,
+            BuilderTarget::Manager(manager) => manager.acquire(handle.key()),
+        };
+
+        let remembered = if self.remember {
+            dialog.memory.get(handle.key()).cloned()
+        } else {
+            None
+        };
+        let remembered = remembered.unwrap_or_default();
+
+        let mut filter_set = self
+            .filter_set
+            .or_else(|| FilterSet::parse(self.filters.as_deref()?).ok());
+        // ImGuiFileDialog starts on the first filter and has no call to select one.
+        if let (Some(set), Some(name)) = (&mut filter_set, &remembered.filter) {
+            set.move_to_front(name);
+        }
+
+        // For directory mode, filters should be null
+        let filters = match (M::MODE, &filter_set) {
+            (DialogMode::OpenDirectory, _) => None,
+            (_, Some(set)) => {
+                set.validate().map_err(FileDialogError::InvalidFilter)?;
+                Some(c_string("filters", &set.to_string())?)
+            }
+            (_, None) => Some(c_string(
+                "filters",
+                self.filters.as_deref().unwrap_or(".*"),
+            )?),
+        };
+
+        if let (None, Some(template)) = (&self.file_name, &self.file_name_template) {
+            let directory = self
+                .path
+                .as_deref()
+                .or(remembered.directory.as_deref())
+                .unwrap_or(Path::new("."));
+            let name = template::expand_file_name(template, directory, SystemTime::now())
+                .ok_or_else(|| FileDialogError::NoFreeFileName {
+                    template: template.clone(),
+                })?;
+            self.file_name = Some(name.into());
+        }
+        let location = match (&self.path, &self.file_name, remembered.file) {
+            (None, None, Some(file)) if M::MODE != DialogMode::OpenDirectory => {
+                Location::FilePathName(path_to_c_string(&file)?)
+            }
+            _ => {
+                let path = self.path.or(remembered.directory);
+                Location::Path {
+                    path: path_to_c_string(path.as_deref().unwrap_or(Path::new(".")))?,
+                    file_name: path_to_c_string(Path::new(
+                        self.file_name.as_deref().unwrap_or_default(),
+                    ))?,
+                }
+            }
+        };
+
+        let request = OpenRequest {
+            title,
+            filters,
+            location,
+            max_selection: self.max_selection,
+            modal: self.modal,
+            flags: self.flags,
+            data: Box::new(DialogData {
+                handle: handle.clone(),
+                mode: M::MODE,
+                remember: self.remember,
+                filter_set,
+                extension_policy: self.extension_policy,
+                ctx: dialog.ptr,
+                pane: self.pane,
+                validator: self.validator,
+                user_data: self.user_data,
+                completion: self.completion,
+                #[cfg(feature = "serde")]
+                document: self.document,
+                ui: std::ptr::null(),
+            }),
+        };
+
+        let conflict = dialog.is_opened() && !dialog.is_key_opened(&handle);
+        match self.policy.unwrap_or(dialog.policy) {
+            OpenPolicy::Reject if conflict => {
+                return Err(FileDialogError::AlreadyOpen {
+                    key: handle.key().to_owned(),
+                });
+            }
+            OpenPolicy::Queue if conflict => dialog.queue.push_back(request),
+            _ => {
+                if !request.open(dialog) {
+                    return Err(FileDialogError::Misuse(
+                        "ImGuiFileDialog did not open the dialog",
+                    ));
+                }
+            }
+        }
+        Ok(handle.cast())
+    }
+}
+
+impl<'a, M: PathMode> FileDialogBuilder<'a, M> {
+    /// Call `callback` with the result once the dialog finishes.
+    ///
+    /// [`FileDialog::display`] then closes the dialog by itself. The callback is
+    /// also called, with [`DialogEvent::Cancelled`], if the dialog is closed or
+    /// replaced before the user finishes it. Replaces any earlier
+    /// [`send_to`](Self::send_to).
+    pub fn on_complete<F>(mut self, callback: F) -> Self
+    where
+        F: FnOnce(DialogEvent) + Send + 'static,
+    {
+        self.completion = Some(Completion::Callback(Box::new(callback)));
+        self
+    }
+
+    /// Send the result to `sender` once the dialog finishes.
+    ///
+    /// Lets background workers receive the chosen paths without access to the
+    /// [`FileDialog`]. Behaves like [`on_complete`](Self::on_complete) otherwise.
+    pub fn send_to(mut self, sender: Sender<DialogEvent>) -> Self {
+        self.completion = Some(Completion::Channel(sender));
+        self
+    }
+
+    /// Open the dialog and return a future resolving to the chosen paths.
+    ///
+    /// The future resolves once the user finishes the dialog, which requires the
+    /// dialog to be displayed every frame, e.g. by [`DialogManager::render`].
+    pub fn build_future<K: DialogKey + ?Sized>(
+        mut self,
+        key: &K,
+    ) -> Result<DialogFuture, FileDialogError> {
+        let (completion, state) = DialogFuture::completion();
+        self.completion = Some(completion);
+        let handle = self.build(key)?;
+        Ok(DialogFuture::new(handle.into_untyped(), state))
+    }
+}
+
+impl<'a, M: FileMode> FileDialogBuilder<'a, M> {
     /// Set the file filters.
     ///
     /// Format: ".ext1,.ext2,.ext3" or "Description{.ext1,.ext2}" or ".*" for all files.
+    ///
+    /// See [`filter_set`](Self::filter_set) for a typed alternative, and
+    /// [`FilterSet::parse`] to check strings from config files.
     pub fn filters(mut self, filters: &str) -> Self {
-        self.filters = Some(CString::new(filters).unwrap());
+        self.filters = Some(filters.to_owned());
+        self.filter_set = None;
         self
     }
 
-    /// Set the initial directory path.
-    pub fn path(mut self, path: impl AsRef<std::path::Path>) -> Self {
-        self.path = Some(CString::new(path.as_ref().to_string_lossy().as_ref()).unwrap());
+    /// Set the file filters from a typed [`FilterSet`].
+    ///
+    /// The set is validated by [`build`](Self::build), and the filter the user
+    /// picks is available from [`FileDialog::selected_filter`].
+    pub fn filter_set(mut self, filters: FilterSet) -> Self {
+        self.filter_set = Some(filters);
+        self.filters = None;
         self
     }
 
-    /// Set the default file name (for save dialogs).
-    pub fn file_name(mut self, name: &str) -> Self {
-        self.file_name = Some(CString::new(name).unwrap());
+    /// Set the default file name.
+    pub fn file_name(mut self, name: impl AsRef<OsStr>) -> Self {
+        self.file_name = Some(name.as_ref().to_os_string());
         self
     }
+}
 
+impl<'a> OpenFileBuilder<'a> {
     /// Allow multiple file selection.
     ///
     /// * `0` means infinite selection
@@ -312,111 +1203,439 @@ impl<'a> FileDialogBuilder<'a> {
         self.max_selection = max;
         self
     }
+}
 
-    /// Make this a modal dialog.
-    pub fn modal(mut self) -> Self {
-        self.modal = true;
+impl<'a, M: SaveMode> FileDialogBuilder<'a, M> {
+    /// Generate the default file name from a template when the dialog is built.
+    ///
+    /// `{date}` expands to `YYYY-MM-DD` and `{time}` to `HH-MM-SS`, both in UTC,
+    /// not local time. `{n}` counts up from 1 until no file with that name exists
+    /// in the start directory; without `{n}`, a taken name gets a counter before
+    /// its extension, e.g. `report (2).csv`. If no free name is found,
+    /// [`build`](FileDialogBuilder::build) fails with
+    /// [`FileDialogError::NoFreeFileName`]. An explicit
+    /// [`file_name`](FileDialogBuilder::file_name) takes precedence.
+    ///
+    /// ```no_run
+    /// # fn main() -> Result<(), imgui_filedialog::FileDialogError> {
+    /// # let mut dialog = imgui_filedialog::FileDialog::new();
+    /// dialog
+    ///     .save_file()
+    ///     .filters(".csv")
+    ///     .file_name_template("export-{date}-{n}.csv")
+    ///     .build("export")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn file_name_template(mut self, template: &str) -> Self {
+        self.file_name_template = Some(template.to_owned());
         self
     }
 
-    /// Show confirmation dialog when overwriting files (for save dialogs).
-    pub fn confirm_overwrite(mut self) -> Self {
-        self.flags |= sys::ImGuiFileDialogFlags_ConfirmOverwrite;
+    /// Choose what happens to a file name typed without an extension.
+    ///
+    /// See [`ExtensionPolicy`].
+    pub fn extension_policy(mut self, policy: ExtensionPolicy) -> Self {
+        self.extension_policy = policy;
         self
     }
 
-    /// Open the dialog with the configured options.
-    ///
-    /// # Arguments
-    /// * `key` - Unique key to identify this dialog instance
-    pub fn build(self, key: &str) {
-        let key_c = CString::new(key).unwrap();
-
-        let default_title = match self.mode {
-            DialogMode::OpenFile => CString::new("Open File").unwrap(),
-            DialogMode::OpenDirectory => CString::new("Select Directory").unwrap(),
-            DialogMode::SaveFile => CString::new("Save File").unwrap(),
-        };
+    /// Show confirmation dialog when overwriting files.
+    pub fn confirm_overwrite(self) -> Self {
+        self.flags(DialogFlags::CONFIRM_OVERWRITE)
+    }
+}
 
-        let title = self.title.as_ref().unwrap_or(&default_title);
+/// Where a dialog starts.
+enum Location {
+    /// A directory and a default file name.
+    Path { path: CString, file_name: CString },
+    /// A full file path, preselecting the file and the filter matching its extension.
+    FilePathName(CString),
+}
 
-        // For directory mode, filters should be null
-        let filters_ptr = match self.mode {
-            DialogMode::OpenDirectory => std::ptr::null(),
-            _ => {
-                let default_filter = CString::new(".*").unwrap();
-                self.filters
-                    .as_ref()
-                    .map(|f| f.as_ptr())
-                    .unwrap_or(default_filter.as_ptr())
-            }
-        };
+/// A fully validated dialog, ready to be opened now or when the current one closes.
+struct OpenRequest {
+    title: CString,
+    filters: Option<CString>,
+    location: Location,
+    max_selection: i32,
+    modal: bool,
+    flags: DialogFlags,
+    data: Box<DialogData>,
+}
 
-        let default_path = CString::new(".").unwrap();
-        let path = self.path.as_ref().unwrap_or(&default_path);
+impl OpenRequest {
+    /// Open the dialog in place of whatever `dialog` shows, returning `true` if
+    /// ImGuiFileDialog took it.
+    fn open(self, dialog: &mut FileDialog) -> bool {
+        // ImGuiFileDialog ignores open calls while a dialog is shown, even under
+        // the same key, and would go on using the state of the old dialog.
+        dialog.close_current();
 
-        let default_filename = CString::new("").unwrap();
-        let filename = self.file_name.as_ref().unwrap_or(&default_filename);
+        let filters_ptr = self
+            .filters
+            .as_ref()
+            .map(|f| f.as_ptr())
+            .unwrap_or(std::ptr::null());
+        let pane_width = self.data.pane.as_ref().map(|pane| pane.width);
+        let data = NonNull::from(Box::leak(self.data));
+        let user_datas = data.as_ptr() as *mut c_void;
+        let key = unsafe { (*data.as_ptr()).handle.c_str() };
 
         unsafe {
-            if self.modal {
-                sys::IGFD_OpenModal(
-                    self.dialog.ptr,
-                    key_c.as_ptr(),
-                    title.as_ptr(),
-                    filters_ptr,
-                    path.as_ptr(),
-                    filename.as_ptr(),
-                    self.max_selection,
-                    std::ptr::null_mut(),
-                    self.flags,
-                );
-            } else {
-                sys::IGFD_OpenDialog(
-                    self.dialog.ptr,
-                    key_c.as_ptr(),
-                    title.as_ptr(),
-                    filters_ptr,
-                    path.as_ptr(),
-                    filename.as_ptr(),
-                    self.max_selection,
-                    std::ptr::null_mut(),
-                    self.flags,
-                );
+            match (&self.location, pane_width) {
+                (Location::Path { path, file_name }, Some(width)) => {
+                    let open = if self.modal {
+                        sys::IGFD_OpenPaneModal
+                    } else {
+                        sys::IGFD_OpenPaneDialog
+                    };
+                    open(
+                        dialog.ptr,
+                        key.as_ptr(),
+                        self.title.as_ptr(),
+                        filters_ptr,
+                        path.as_ptr(),
+                        file_name.as_ptr(),
+                        Some(side_pane_trampoline),
+                        width,
+                        self.max_selection,
+                        user_datas,
+                        self.flags.bits(),
+                    );
+                }
+                (Location::Path { path, file_name }, None) => {
+                    let open = if self.modal {
+                        sys::IGFD_OpenModal
+                    } else {
+                        sys::IGFD_OpenDialog
+                    };
+                    open(
+                        dialog.ptr,
+                        key.as_ptr(),
+                        self.title.as_ptr(),
+                        filters_ptr,
+                        path.as_ptr(),
+                        file_name.as_ptr(),
+                        self.max_selection,
+                        user_datas,
+                        self.flags.bits(),
+                    );
+                }
+                (Location::FilePathName(file_path_name), Some(width)) => {
+                    let open = if self.modal {
+                        sys::IGFD_OpenPaneModal2
+                    } else {
+                        sys::IGFD_OpenPaneDialog2
+                    };
+                    open(
+                        dialog.ptr,
+                        key.as_ptr(),
+                        self.title.as_ptr(),
+                        filters_ptr,
+                        file_path_name.as_ptr(),
+                        Some(side_pane_trampoline),
+                        width,
+                        self.max_selection,
+                        user_datas,
+                        self.flags.bits(),
+                    );
+                }
+                (Location::FilePathName(file_path_name), None) => {
+                    let open = if self.modal {
+                        sys::IGFD_OpenModal2
+                    } else {
+                        sys::IGFD_OpenDialog2
+                    };
+                    open(
+                        dialog.ptr,
+                        key.as_ptr(),
+                        self.title.as_ptr(),
+                        filters_ptr,
+                        file_path_name.as_ptr(),
+                        self.max_selection,
+                        user_datas,
+                        self.flags.bits(),
+                    );
+                }
+            }
+        }
+
+        if unsafe { sys::IGFD_IsKeyOpened(dialog.ptr, key.as_ptr()) } {
+            debug_assert!(dialog.data.is_none());
+            debug_assert_eq!(unsafe { sys::IGFD_GetUserDatas(dialog.ptr) }, user_datas);
+            dialog.data = Some(data);
+            true
+        } else {
+            drop(unsafe { Box::from_raw(data.as_ptr()) });
+            false
+        }
+    }
+}
+
+// ============================================================
+// Side pane
+// ============================================================
+
+/// Context handed to a side pane closure each frame.
+pub struct PaneContext<'a> {
+    ui: &'a Ui<'a>,
+    filter: &'a str,
+    can_continue: &'a mut bool,
+}
+
+impl<'a> PaneContext<'a> {
+    /// The imgui frame the dialog is being drawn in.
+    pub fn ui(&self) -> &'a Ui<'a> {
+        self.ui
+    }
+
+    /// The filter currently selected in the dialog.
+    pub fn filter(&self) -> &str {
+        self.filter
+    }
+
+    /// Returns `true` if the OK button is currently enabled.
+    pub fn can_continue(&self) -> bool {
+        *self.can_continue
+    }
+
+    /// Enable or disable the OK button of the dialog.
+    ///
+    /// ImGuiFileDialog names this flag `cantContinue`, but the OK button is
+    /// only shown while it is `true`.
+    pub fn set_can_continue(&mut self, can_continue: bool) {
+        *self.can_continue = can_continue;
+    }
+}
+
+type PaneFn = Box<dyn FnMut(&mut PaneContext<'_>) + Send>;
+
+type ValidateFn = Box<dyn FnMut(&[PathBuf]) -> Result<(), String> + Send>;
+
+/// Width of the side pane added for [`FileDialogBuilder::validate`] messages.
+const VALIDATION_PANE_WIDTH: f32 = 200.0;
+
+/// Color of [`FileDialogBuilder::validate`] messages.
+const VALIDATION_ERROR_COLOR: [f32; 4] = [1.0, 0.4, 0.4, 1.0];
+
+struct SidePane {
+    width: f32,
+    draw: PaneFn,
+}
+
+/// Rust-side state of an open dialog, owned by [`FileDialog`].
+struct DialogData {
+    handle: DialogHandle,
+    mode: DialogMode,
+    /// Record the result in [`FileDialog::memory`] when confirmed.
+    remember: bool,
+    /// The filters of the dialog, parsed from the string if needed.
+    filter_set: Option<FilterSet>,
+    extension_policy: ExtensionPolicy,
+    /// The ImGuiFileDialog context the dialog is open in.
+    ctx: *mut sys::ImGuiFileDialog,
+    pane: Option<SidePane>,
+    validator: Option<ValidateFn>,
+    user_data: Option<Box<dyn Any + Send>>,
+    completion: Option<Completion>,
+    /// The value to write, for [`FileDialog::save_document`].
+    #[cfg(feature = "serde")]
+    document: Option<PendingDocument>,
+    /// Set only for the duration of [`FileDialog::display_with_ui`].
+    ui: *const Ui<'static>,
+}
+
+impl Drop for DialogData {
+    fn drop(&mut self) {
+        // The dialog went away without finishing.
+        if let Some(completion) = self.completion.take() {
+            completion.complete(&self.handle, DialogOutcome::Cancelled);
+        }
+    }
+}
+
+unsafe extern "C" fn side_pane_trampoline(
+    filter: *const c_char,
+    user_datas: *mut c_void,
+    cant_continue: *mut bool,
+) {
+    if user_datas.is_null() || cant_continue.is_null() {
+        return;
+    }
+
+    let data = &mut *(user_datas as *mut DialogData);
+    let ui = data.ui.as_ref();
+
+    if let (Some(pane), Some(ui)) = (data.pane.as_mut(), ui) {
+        let filter = if filter.is_null() {
+            Cow::Borrowed("")
+        } else {
+            CStr::from_ptr(filter).to_string_lossy()
+        };
+        let mut ctx = PaneContext {
+            ui,
+            filter: &filter,
+            can_continue: &mut *cant_continue,
+        };
+
+        // Unwinding into C++ would abort the host process, so a panicking pane is
+        // disabled instead.
+        if panic::catch_unwind(AssertUnwindSafe(|| (pane.draw)(&mut ctx))).is_err() {
+            data.pane = None;
+        }
+    }
+
+    let Some(validator) = data.validator.as_mut() else {
+        return;
+    };
+    let candidate = candidate(data.ctx, data.mode);
+    if candidate.is_empty() {
+        return;
+    }
+    match panic::catch_unwind(AssertUnwindSafe(|| validator(&candidate))) {
+        Ok(Ok(())) => {}
+        Ok(Err(message)) => {
+            *cant_continue = false;
+            if let Some(ui) = ui {
+                let _color = ui.push_style_color(StyleColor::Text, VALIDATION_ERROR_COLOR);
+                ui.text_wrapped(message);
             }
         }
+        Err(_) => data.validator = None,
     }
 }
 
+/// Get the paths the user would confirm if they pressed OK now.
+unsafe fn candidate(ctx: *mut sys::ImGuiFileDialog, mode: DialogMode) -> Vec<PathBuf> {
+    if mode == DialogMode::OpenFile {
+        let files = Selection::new(sys::IGFD_GetSelection(ctx), None)
+            .map(Selection::into_vec)
+            .unwrap_or_default();
+        // A name typed into the file name box leaves the selection empty, see
+        // `mode::OpenFile`.
+        if !files.is_empty() {
+            return files;
+        }
+    }
+    if mode != DialogMode::OpenDirectory {
+        let name = sys::IGFD_GetCurrentFileName(ctx);
+        if name.is_null() {
+            return Vec::new();
+        }
+        let empty = *name == 0;
+        libc::free(name as *mut _);
+        if empty {
+            return Vec::new();
+        }
+    }
+    take_path(sys::IGFD_GetFilePathName(ctx))
+        .ok()
+        .flatten()
+        .into_iter()
+        .collect()
+}
+
 /// Collection of selected files from the dialog.
 pub struct Selection {
     inner: sys::IGFD_Selection,
+    filter: Option<Filter>,
 }
 
 impl Selection {
-    fn new(inner: sys::IGFD_Selection) -> Self {
-        Self { inner }
+    /// Take ownership of `inner`, checking that every entry can be represented.
+    fn new(inner: sys::IGFD_Selection, filter: Option<Filter>) -> Result<Self, FileDialogError> {
+        let selection = Self { inner, filter };
+        for index in 0..selection.len() {
+            unsafe { selection.try_entry(index)? };
+        }
+        Ok(selection)
     }
 
     /// Get the number of selected files.
     pub fn count(&self) -> usize {
-        self.inner.count
+        self.len()
+    }
+
+    /// Get the entry at `index`, or `None` if it is out of bounds.
+    pub fn get(&self, index: usize) -> Option<SelectedEntry<'_>> {
+        if index < self.len() {
+            Some(unsafe { self.entry(index) })
+        } else {
+            None
+        }
+    }
+
+    /// Get an iterator over the selected entries, borrowed from the selection.
+    pub fn entries(&self) -> impl ExactSizeIterator<Item = SelectedEntry<'_>> + '_ {
+        (0..self.len()).map(move |i| unsafe { self.entry(i) })
+    }
+
+    /// Get an iterator over the selected file names, as displayed by the dialog.
+    pub fn file_names(&self) -> impl Iterator<Item = &OsStr> + '_ {
+        self.entries().map(|entry| entry.name)
     }
 
     /// Get an iterator over selected file paths.
     pub fn files(&self) -> impl Iterator<Item = PathBuf> + '_ {
-        (0..self.inner.count).map(move |i| unsafe {
-            let pair = &*self.inner.table.add(i);
-            ptr_to_pathbuf(pair.filePathName)
-        })
+        self.entries().map(|entry| entry.path.to_path_buf())
+    }
+
+    /// Get the filter that was selected when the dialog was confirmed.
+    ///
+    /// See [`FileDialog::selected_filter`].
+    pub fn filter(&self) -> Option<&Filter> {
+        self.filter.as_ref()
     }
 
     /// Get all selected file paths as a vector.
     pub fn into_vec(self) -> Vec<PathBuf> {
         self.files().collect()
     }
+
+    /// Number of readable entries, treating a missing table as empty.
+    fn len(&self) -> usize {
+        if self.inner.table.is_null() {
+            0
+        } else {
+            self.inner.count
+        }
+    }
+
+    /// # Safety
+    /// `index` must be less than [`len`](Self::len).
+    unsafe fn entry(&self, index: usize) -> SelectedEntry<'_> {
+        self.try_entry(index)
+            .expect("selection entries are checked on creation")
+    }
+
+    /// # Safety
+    /// `index` must be less than [`len`](Self::len).
+    unsafe fn try_entry(&self, index: usize) -> Result<SelectedEntry<'_>, FileDialogError> {
+        let pair = &*self.inner.table.add(index);
+        Ok(SelectedEntry {
+            name: cstr_to_os_str(cstr_or_empty(pair.fileName))?,
+            path: cstr_to_path(cstr_or_empty(pair.filePathName))?,
+        })
+    }
+}
+
+/// A single selected file, borrowed from a [`Selection`].
+///
+/// Both fields borrow from the selection. On Unix they keep the raw bytes of
+/// the name, so non-UTF-8 file names survive unchanged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SelectedEntry<'a> {
+    /// The file name, exactly as displayed by the dialog.
+    pub name: &'a OsStr,
+    /// The full path of the file.
+    pub path: &'a Path,
 }
 
+// SAFETY: a selection owns its strings, which are only freed on drop.
+unsafe impl Send for Selection {}
+
 impl Drop for Selection {
     fn drop(&mut self) {
         unsafe { sys::IGFD_Selection_DestroyContent(&mut self.inner) }
@@ -442,9 +1661,10 @@ impl FileDialog {
     }
 
     /// Deserialize bookmarks from a saved string.
-    pub fn deserialize_bookmarks(&mut self, bookmarks: &str) {
-        let bookmarks_c = CString::new(bookmarks).unwrap();
+    pub fn deserialize_bookmarks(&mut self, bookmarks: &str) -> Result<(), FileDialogError> {
+        let bookmarks_c = c_string("bookmarks", bookmarks)?;
         unsafe { sys::IGFD_DeserializeBookmarks(self.ptr, bookmarks_c.as_ptr()) }
+        Ok(())
     }
 }
 
@@ -463,10 +1683,71 @@ impl FileDialog {
 // Helper functions
 // ============================================================
 
+fn c_string(field: &'static str, s: &str) -> Result<CString, FileDialogError> {
+    CString::new(s).map_err(|e| FileDialogError::InvalidString {
+        field,
+        position: e.nul_position(),
+    })
+}
+
+/// Convert a path for ImGuiFileDialog.
+///
+/// On Unix the raw bytes are passed through. Elsewhere ImGuiFileDialog expects
+/// UTF-8, so paths that are not valid Unicode are rejected rather than mangled.
+fn path_to_c_string(path: &Path) -> Result<CString, FileDialogError> {
+    #[cfg(unix)]
+    let bytes = {
+        use std::os::unix::ffi::OsStrExt;
+        Some(path.as_os_str().as_bytes())
+    };
+    #[cfg(not(unix))]
+    let bytes = path.to_str().map(str::as_bytes);
+
+    bytes
+        .and_then(|bytes| CString::new(bytes).ok())
+        .ok_or_else(|| FileDialogError::InvalidPath(path.to_path_buf()))
+}
+
+/// Convert a string returned by ImGuiFileDialog, keeping the raw bytes on Unix.
+///
+/// ImGuiFileDialog converts native paths to UTF-8 on other platforms, where
+/// anything else cannot be represented and is rejected rather than mangled.
+fn cstr_to_os_str(s: &CStr) -> Result<&OsStr, FileDialogError> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        Ok(OsStr::from_bytes(s.to_bytes()))
+    }
+    #[cfg(not(unix))]
+    {
+        s.to_str()
+            .map(OsStr::new)
+            .map_err(|_| FileDialogError::NonUnicodePath(s.to_bytes().to_vec()))
+    }
+}
+
+fn cstr_to_path(s: &CStr) -> Result<&Path, FileDialogError> {
+    cstr_to_os_str(s).map(Path::new)
+}
+
 unsafe fn ptr_to_string(ptr: *mut c_char) -> String {
     CStr::from_ptr(ptr).to_string_lossy().into_owned()
 }
 
-unsafe fn ptr_to_pathbuf(ptr: *mut c_char) -> PathBuf {
-    PathBuf::from(CStr::from_ptr(ptr).to_string_lossy().as_ref())
+unsafe fn cstr_or_empty<'a>(ptr: *const c_char) -> &'a CStr {
+    if ptr.is_null() {
+        Default::default()
+    } else {
+        CStr::from_ptr(ptr)
+    }
+}
+
+/// Convert and free a path allocated by ImGuiFileDialog, which may be null.
+unsafe fn take_path(ptr: *mut c_char) -> Result<Option<PathBuf>, FileDialogError> {
+    if ptr.is_null() {
+        return Ok(None);
+    }
+    let path = cstr_to_path(CStr::from_ptr(ptr)).map(Path::to_path_buf);
+    libc::free(ptr as *mut _);
+    path.map(Some)
 }
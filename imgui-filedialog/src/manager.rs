@@ -0,0 +1,280 @@
+//! Pool of file dialogs for showing several dialogs at once.
+
+use std::path::PathBuf;
+
+use arcdps_imgui::Ui;
+
+use crate::{
+    mode, BuilderTarget, DialogFuture, DialogHandle, DialogKey, DialogMemory, DialogOutcome,
+    DisplayOptions, ExtensionStyles, FileDialog, FileDialogBuilder, FileDialogError, MruList,
+    OpenDirectoryBuilder, OpenFileBuilder, OpenPolicy, SaveFileBuilder,
+};
+
+/// Number of idle [`FileDialog`] instances kept for reuse by default.
+const DEFAULT_MAX_IDLE: usize = 2;
+
+/// Manages several [`FileDialog`] instances so dialogs with different keys can
+/// be open at the same time.
+///
+/// Each open key gets its own ImGuiFileDialog context. Contexts of finished
+/// dialogs go back to a pool and are reused for the next key.
+///
+/// # Example
+///
+/// ```no_run
+/// use imgui_filedialog::{DialogManager, DialogOutcome};
+///
+/// # fn frame(ui: &arcdps_imgui::Ui) -> Result<(), imgui_filedialog::FileDialogError> {
+/// let mut manager = DialogManager::new();
+///
+/// manager.open_file().title("Input").build("input")?;
+/// manager.save_file().title("Output").build("output")?;
+///
+/// // In your render loop:
+/// for (handle, outcome) in manager.render(ui) {
+///     if let DialogOutcome::Accepted { value: paths, .. } = outcome {
+///         println!("{}: {:?}", handle.key(), paths);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct DialogManager {
+    open: Vec<FileDialog>,
+    idle: Vec<FileDialog>,
+    max_idle: usize,
+    options: DisplayOptions,
+    styles: Option<ExtensionStyles>,
+    memory: DialogMemory,
+    recent: MruList,
+}
+
+impl Default for DialogManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DialogManager {
+    /// Create an empty manager.
+    pub fn new() -> Self {
+        Self {
+            open: Vec::new(),
+            idle: Vec::new(),
+            max_idle: DEFAULT_MAX_IDLE,
+            options: DisplayOptions::new(),
+            styles: None,
+            memory: DialogMemory::new(),
+            recent: MruList::new(),
+        }
+    }
+
+    /// Set how many finished [`FileDialog`] instances are kept for reuse.
+    ///
+    /// Instances beyond this count are destroyed when their dialog finishes.
+    pub fn set_max_idle(&mut self, max_idle: usize) {
+        self.max_idle = max_idle;
+        self.idle.truncate(max_idle);
+    }
+
+    /// Set the window options used by [`render`](Self::render).
+    pub fn set_display_options(&mut self, options: DisplayOptions) {
+        self.options = options;
+    }
+
+    /// Set extension styles applied to every dialog handed out by the manager.
+    ///
+    /// Replaces the styles of all current instances.
+    pub fn set_extension_styles(&mut self, styles: ExtensionStyles) -> Result<(), FileDialogError> {
+        for dialog in self.open.iter_mut().chain(self.idle.iter_mut()) {
+            dialog.reset_extension_styles(&styles)?;
+        }
+        self.styles = Some(styles);
+        Ok(())
+    }
+
+    /// Get the states remembered by dialogs opened with
+    /// [`FileDialogBuilder::remember`](crate::FileDialogBuilder::remember),
+    /// shared by all instances.
+    pub fn memory(&self) -> &DialogMemory {
+        &self.memory
+    }
+
+    /// Get the remembered states for editing.
+    ///
+    /// Changes apply to dialogs opened afterwards.
+    pub fn memory_mut(&mut self) -> &mut DialogMemory {
+        &mut self.memory
+    }
+
+    /// Replace the remembered states, e.g. with ones loaded from disk.
+    pub fn set_memory(&mut self, memory: DialogMemory) {
+        self.memory = memory;
+    }
+
+    /// Get the files and directories chosen in all managed dialogs.
+    pub fn recent(&self) -> &MruList {
+        &self.recent
+    }
+
+    /// Get the recent files and directories for editing.
+    pub fn recent_mut(&mut self) -> &mut MruList {
+        &mut self.recent
+    }
+
+    /// Open a file selection dialog.
+    ///
+    /// The dialog gets its own instance, picked by the key passed to
+    /// [`FileDialogBuilder::build`](crate::FileDialogBuilder::build).
+    pub fn open_file(&mut self) -> OpenFileBuilder<'_> {
+        FileDialogBuilder::new(BuilderTarget::Manager(self))
+    }
+
+    /// Open a directory selection dialog.
+    ///
+    /// See [`open_file`](Self::open_file).
+    pub fn open_directory(&mut self) -> OpenDirectoryBuilder<'_> {
+        FileDialogBuilder::new(BuilderTarget::Manager(self))
+    }
+
+    /// Open a save file dialog.
+    ///
+    /// See [`open_file`](Self::open_file).
+    pub fn save_file(&mut self) -> SaveFileBuilder<'_> {
+        FileDialogBuilder::new(BuilderTarget::Manager(self))
+    }
+
+    /// Open a file selection dialog for `key` and return a future resolving to
+    /// the selected files.
+    ///
+    /// The future resolves from [`render`](Self::render). Use
+    /// [`open_file`](Self::open_file) and
+    /// [`FileDialogBuilder::build_future`](crate::FileDialogBuilder::build_future)
+    /// to configure the dialog first.
+    pub fn pick_file(&mut self, key: &str) -> Result<DialogFuture, FileDialogError> {
+        self.open_file().build_future(key)
+    }
+
+    /// Open a save file dialog for `key` and return a future resolving to the
+    /// chosen file.
+    ///
+    /// See [`pick_file`](Self::pick_file).
+    pub fn pick_save_file(&mut self, key: &str) -> Result<DialogFuture, FileDialogError> {
+        self.save_file().build_future(key)
+    }
+
+    /// Get the dialog open under `key`.
+    pub fn dialog<K: DialogKey + ?Sized>(&mut self, key: &K) -> Option<&mut FileDialog> {
+        let index = self.position(key)?;
+        Some(&mut self.open[index])
+    }
+
+    /// Check if a dialog is open under `key`.
+    pub fn is_open<K: DialogKey + ?Sized>(&self, key: &K) -> bool {
+        self.position(key).is_some()
+    }
+
+    /// Number of open dialogs.
+    pub fn open_count(&self) -> usize {
+        self.open.iter().filter(|dialog| dialog.is_opened()).count()
+    }
+
+    /// Close the dialog open under `key` without reporting an outcome.
+    pub fn close<K: DialogKey + ?Sized>(&mut self, key: &K) {
+        if let Some(index) = self.position(key) {
+            let mut dialog = self.open.swap_remove(index);
+            let handle = dialog.opened_handle().cloned();
+            dialog.close();
+            self.release(dialog, handle.as_ref());
+        }
+    }
+
+    /// Display all open dialogs.
+    ///
+    /// Call this every frame. Returns the outcome of every dialog the user
+    /// confirmed or cancelled this frame, together with its handle. The outcome
+    /// holds the chosen paths for every kind of dialog: the files of an open
+    /// dialog, the file to write of a save dialog or the chosen directory. Dialogs
+    /// opened as a [`DialogFuture`], or with a callback or channel, deliver
+    /// their result there instead.
+    pub fn render(&mut self, ui: &Ui) -> Vec<(DialogHandle, DialogOutcome<Vec<PathBuf>>)> {
+        let mut finished = Vec::new();
+
+        let mut i = 0;
+        while i < self.open.len() {
+            let dialog = &mut self.open[i];
+            let handle = dialog.opened_handle().cloned();
+            if let Some(handle) = &handle {
+                let paths = handle.clone().cast::<mode::Paths>();
+                match dialog.poll_with(ui, &paths, &self.options) {
+                    DialogOutcome::Pending => {}
+                    outcome => finished.push((handle.clone(), outcome)),
+                }
+            }
+
+            // Finished dialogs, and instances whose builder was never built,
+            // go back to the pool.
+            if self.open[i].is_opened() {
+                i += 1;
+            } else {
+                let dialog = self.open.swap_remove(i);
+                self.release(dialog, handle.as_ref());
+            }
+        }
+
+        finished
+    }
+
+    fn position<K: DialogKey + ?Sized>(&self, key: &K) -> Option<usize> {
+        self.open
+            .iter()
+            .position(|dialog| dialog.is_key_opened(key))
+    }
+
+    /// Get the instance open under `key`, or hand out a pooled one.
+    ///
+    /// Pooled instances start over with the default [`OpenPolicy`] and an empty
+    /// queue, whatever their last user set.
+    pub(crate) fn acquire(&mut self, key: &str) -> &mut FileDialog {
+        if let Some(index) = self.position(key) {
+            return &mut self.open[index];
+        }
+
+        let mut dialog = match self.idle.pop() {
+            Some(dialog) => dialog,
+            None => {
+                let mut dialog = FileDialog::new();
+                if let Some(styles) = &self.styles {
+                    // Already validated by `set_extension_styles`.
+                    let _ = dialog.apply_extension_styles(styles);
+                }
+                dialog
+            }
+        };
+        dialog.set_open_policy(OpenPolicy::default());
+        dialog.clear_queue();
+        dialog.set_memory(self.memory.clone());
+        // Collect only what this dialog adds; merged back on release.
+        dialog.set_recent(MruList::with_limits(
+            self.recent.max_files(),
+            self.recent.max_directories(),
+        ));
+        let index = self.open.len();
+        self.open.push(dialog);
+        &mut self.open[index]
+    }
+
+    /// Return an instance to the pool, keeping what it remembered for `handle`
+    /// and the files it added to the recent list.
+    fn release(&mut self, dialog: FileDialog, handle: Option<&DialogHandle>) {
+        self.recent.merge(dialog.recent());
+        if let Some(handle) = handle {
+            if let Some(state) = dialog.memory().get(handle.key()) {
+                self.memory.insert(handle.key(), state.clone());
+            }
+        }
+        if self.idle.len() < self.max_idle {
+            self.idle.push(dialog);
+        }
+    }
+}
@@ -0,0 +1,294 @@
+//! Per-key memory of the last directory, filter and file of a dialog.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// What a dialog remembers from the last time it was confirmed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DialogState {
+    /// The directory the dialog was in.
+    pub directory: Option<PathBuf>,
+    /// The selected filter, as returned by [`FileDialog::current_filter`](crate::FileDialog::current_filter).
+    pub filter: Option<String>,
+    /// The chosen file.
+    pub file: Option<PathBuf>,
+}
+
+/// Remembered [`DialogState`]s by dialog key.
+///
+/// Dialogs opened with [`FileDialogBuilder::remember`](crate::FileDialogBuilder::remember)
+/// record their state here when they are confirmed, and restore it the next time
+/// they are opened. Save it with [`serialize`](Self::serialize) to keep it across
+/// game restarts.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DialogMemory {
+    states: BTreeMap<String, DialogState>,
+}
+
+impl DialogMemory {
+    /// Create an empty memory.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the remembered state of a key.
+    pub fn get(&self, key: &str) -> Option<&DialogState> {
+        self.states.get(key)
+    }
+
+    /// Set the remembered state of a key, returning the previous one.
+    pub fn insert(&mut self, key: &str, state: DialogState) -> Option<DialogState> {
+        self.states.insert(key.to_owned(), state)
+    }
+
+    /// Forget the state of a key, returning it.
+    pub fn remove(&mut self, key: &str) -> Option<DialogState> {
+        self.states.remove(key)
+    }
+
+    /// Forget all keys.
+    pub fn clear(&mut self) {
+        self.states.clear();
+    }
+
+    /// Iterate over the remembered keys in sorted order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &DialogState)> + '_ {
+        self.states.iter().map(|(key, state)| (key.as_str(), state))
+    }
+
+    /// Serialize the memory to a string for saving.
+    ///
+    /// Each key is stored on its own line. Paths keep their raw bytes on Unix;
+    /// elsewhere, states with a path that is not valid Unicode are left out.
+    pub fn serialize(&self) -> String {
+        let mut out = String::new();
+        for (key, state) in &self.states {
+            let directory = match state.directory.as_deref().map(path_bytes) {
+                Some(None) => continue,
+                directory => directory.flatten(),
+            };
+            let file = match state.file.as_deref().map(path_bytes) {
+                Some(None) => continue,
+                file => file.flatten(),
+            };
+
+            escape_into(&mut out, key.as_bytes());
+            for field in [
+                directory.as_deref(),
+                state.filter.as_deref().map(str::as_bytes),
+                file.as_deref(),
+            ] {
+                out.push('\t');
+                // A marker keeps an empty value apart from a missing one.
+                if let Some(field) = field {
+                    out.push('=');
+                    escape_into(&mut out, field);
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Deserialize a memory saved with [`serialize`](Self::serialize).
+    ///
+    /// Malformed lines are skipped, so a damaged file only loses the affected keys.
+    pub fn deserialize(s: &str) -> Self {
+        let mut memory = Self::new();
+        for line in s.lines() {
+            if let Some((key, state)) = parse_line(line) {
+                memory.states.insert(key, state);
+            }
+        }
+        memory
+    }
+}
+
+fn parse_line(line: &str) -> Option<(String, DialogState)> {
+    let mut fields = line.split('\t');
+    let key = String::from_utf8(unescape(fields.next()?)?).ok()?;
+    let mut field = || -> Option<Option<Vec<u8>>> {
+        match fields.next()? {
+            "" => Some(None),
+            field => unescape(field.strip_prefix('=')?).map(Some),
+        }
+    };
+    let directory = field()?;
+    let filter = field()?;
+    let file = field()?;
+    if fields.next().is_some() {
+        return None;
+    }
+
+    let path = |bytes: Option<Vec<u8>>| match bytes {
+        Some(bytes) => path_from_bytes(&bytes).map(Some),
+        None => Some(None),
+    };
+    let state = DialogState {
+        directory: path(directory)?,
+        filter: filter.map(String::from_utf8).transpose().ok()?,
+        file: path(file)?,
+    };
+    Some((key, state))
+}
+
+fn path_bytes(path: &Path) -> Option<Vec<u8>> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        Some(path.as_os_str().as_bytes().to_vec())
+    }
+    #[cfg(not(unix))]
+    {
+        path.to_str().map(|s| s.as_bytes().to_vec())
+    }
+}
+
+fn path_from_bytes(bytes: &[u8]) -> Option<PathBuf> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStringExt;
+        Some(PathBuf::from(std::ffi::OsString::from_vec(bytes.to_vec())))
+    }
+    #[cfg(not(unix))]
+    {
+        std::str::from_utf8(bytes).ok().map(PathBuf::from)
+    }
+}
+
+/// Escape separators, backslashes and bytes that are not valid UTF-8.
+fn escape_into(out: &mut String, mut bytes: &[u8]) {
+    while !bytes.is_empty() {
+        let (valid, invalid) = match std::str::from_utf8(bytes) {
+            Ok(s) => (s, &[][..]),
+            Err(e) => {
+                let (valid, rest) = bytes.split_at(e.valid_up_to());
+                let invalid_len = e.error_len().unwrap_or(rest.len());
+                (
+                    std::str::from_utf8(valid).unwrap_or_default(),
+                    &rest[..invalid_len],
+                )
+            }
+        };
+
+        for c in valid.chars() {
+            match c {
+                '\\' => out.push_str("\\\\"),
+                '\t' => out.push_str("\\t"),
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                c => out.push(c),
+            }
+        }
+        for byte in invalid {
+            out.push_str(&format!("\\x{:02x}", byte));
+        }
+
+        bytes = &bytes[valid.len() + invalid.len()..];
+    }
+}
+
+/// Reverse [`escape_into`], returning `None` for malformed escapes.
+fn unescape(s: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(s.len());
+    let mut bytes = s.bytes();
+    while let Some(byte) = bytes.next() {
+        if byte != b'\\' {
+            out.push(byte);
+            continue;
+        }
+        match bytes.next()? {
+            b'\\' => out.push(b'\\'),
+            b't' => out.push(b'\t'),
+            b'n' => out.push(b'\n'),
+            b'r' => out.push(b'\r'),
+            b'x' => {
+                let hex = [bytes.next()?, bytes.next()?];
+                let hex = std::str::from_utf8(&hex).ok()?;
+                out.push(u8::from_str_radix(hex, 16).ok()?);
+            }
+            _ => return None,
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(memory: &DialogMemory) -> DialogMemory {
+        DialogMemory::deserialize(&memory.serialize())
+    }
+
+    fn state(directory: Option<&str>, filter: Option<&str>, file: Option<&str>) -> DialogState {
+        DialogState {
+            directory: directory.map(PathBuf::from),
+            filter: filter.map(str::to_owned),
+            file: file.map(PathBuf::from),
+        }
+    }
+
+    #[test]
+    fn round_trips_separators_and_escapes() {
+        let mut memory = DialogMemory::new();
+        memory.insert(
+            "key\twith\ttabs",
+            state(
+                Some("dir\nwith\r\nnewlines"),
+                Some("Logs{.evtc,.zevtc}"),
+                Some("100%\\file\\x41.txt"),
+            ),
+        );
+        memory.insert("%s\\", state(Some("="), Some("\\t"), Some("=x")));
+        assert_eq!(memory.serialize().lines().count(), 2);
+        assert_eq!(round_trip(&memory), memory);
+    }
+
+    #[test]
+    fn keeps_empty_values_apart_from_missing_ones() {
+        let mut memory = DialogMemory::new();
+        memory.insert("", state(Some(""), Some(""), Some("")));
+        memory.insert("missing", state(None, None, None));
+        memory.insert("mixed", state(None, Some(""), Some("a.txt")));
+        assert_eq!(round_trip(&memory), memory);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn round_trips_non_utf8_paths() {
+        use std::os::unix::ffi::OsStringExt;
+
+        let path = PathBuf::from(std::ffi::OsString::from_vec(
+            b"/logs/\xff\xfe.evtc".to_vec(),
+        ));
+        let mut memory = DialogMemory::new();
+        memory.insert(
+            "log",
+            DialogState {
+                directory: path.parent().map(Path::to_path_buf),
+                filter: None,
+                file: Some(path),
+            },
+        );
+        assert_eq!(round_trip(&memory), memory);
+    }
+
+    #[test]
+    fn skips_malformed_lines() {
+        let memory = DialogMemory::deserialize(concat!(
+            "ok\t=dir\t\t=file\n",
+            "too_few\t=dir\t\n",
+            "too_many\t\t\t\t\n",
+            "no_marker\tdir\t\t\n",
+            "bad_escape\t=\\q\t\t\n",
+            "bad_filter\t\t=\\xff\t\n",
+            "\\xff\t\t\t\n",
+        ));
+        assert_eq!(memory.iter().count(), 1);
+        assert_eq!(
+            memory.get("ok"),
+            Some(&state(Some("dir"), None, Some("file")))
+        );
+    }
+}
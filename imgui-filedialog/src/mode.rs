@@ -0,0 +1,226 @@
+//! Marker types tying builders and handles to a dialog mode.
+//!
+//! [`FileDialog::open_file`], [`FileDialog::save_file`] and
+//! [`FileDialog::open_directory`] return builders typed by these markers, so
+//! options that make no sense for a mode do not exist on its builder, and the
+//! [`DialogHandle`](crate::DialogHandle) returned by `build` polls to the result
+//! type of its mode.
+//!
+//! With the `serde` feature, [`FileDialog::open_document`] and
+//! [`FileDialog::save_document`] add modes that read and write the chosen file.
+
+#[cfg(feature = "serde")]
+use std::marker::PhantomData;
+use std::path::PathBuf;
+
+#[cfg(feature = "serde")]
+use serde::de::DeserializeOwned;
+
+#[cfg(feature = "serde")]
+use crate::DocumentError;
+
+use crate::{DialogMode, FileDialog, FileDialogError, SaveTarget, Selection};
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// A dialog mode, determining what a confirmed dialog returns.
+pub trait Mode: private::Sealed {
+    /// The result of a confirmed dialog.
+    type Output;
+
+    /// Read the result of a confirmed dialog.
+    #[doc(hidden)]
+    fn output(dialog: &FileDialog) -> Result<Option<Self::Output>, FileDialogError>;
+}
+
+/// Mode of handles created from a plain key, returning the raw [`Selection`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Any {}
+
+/// Mode of [`OpenFileBuilder`](crate::OpenFileBuilder), returning the chosen files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OpenFile {}
+
+/// Mode of [`SaveFileBuilder`](crate::SaveFileBuilder), returning a [`SaveTarget`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SaveFile {}
+
+/// Mode of [`OpenDirectoryBuilder`](crate::OpenDirectoryBuilder), returning the chosen directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OpenDirectory {}
+
+/// Mode of [`OpenDocumentBuilder`](crate::OpenDocumentBuilder), returning the parsed document.
+#[cfg(feature = "serde")]
+pub struct OpenDocument<T> {
+    _never: std::convert::Infallible,
+    _document: PhantomData<fn() -> T>,
+}
+
+/// Mode of [`SaveDocumentBuilder`](crate::SaveDocumentBuilder), returning the
+/// [`SaveTarget`] the document was written to.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SaveDocument {}
+
+/// Mode used where dialogs of any builder are handled together, e.g. by
+/// [`DialogManager::render`](crate::DialogManager::render), returning the
+/// chosen paths of the mode the dialog was opened in.
+pub(crate) enum Paths {}
+
+/// Modes a builder can be created for.
+pub trait BuilderMode: Mode {
+    #[doc(hidden)]
+    const MODE: DialogMode;
+}
+
+/// Modes choosing files, whose builders take filters and a file name.
+pub trait FileMode: BuilderMode {}
+
+/// Modes choosing a file to write, whose builders take save options.
+pub trait SaveMode: FileMode {}
+
+/// Modes returning plain paths, whose builders can deliver the result by
+/// callback, channel or future.
+///
+/// The document modes read or write their file when polled, so their results
+/// are only available from [`FileDialog::poll`].
+pub trait PathMode: BuilderMode {}
+
+impl private::Sealed for Any {}
+
+impl Mode for Any {
+    type Output = Selection;
+
+    fn output(dialog: &FileDialog) -> Result<Option<Selection>, FileDialogError> {
+        dialog.selection()
+    }
+}
+
+impl private::Sealed for Paths {}
+
+impl Mode for Paths {
+    type Output = Vec<PathBuf>;
+
+    fn output(dialog: &FileDialog) -> Result<Option<Vec<PathBuf>>, FileDialogError> {
+        match dialog.opened_mode() {
+            Some(DialogMode::SaveFile) => {
+                Ok(SaveFile::output(dialog)?.map(|target| vec![target.into_path()]))
+            }
+            Some(DialogMode::OpenDirectory) => {
+                Ok(OpenDirectory::output(dialog)?.map(|path| vec![path]))
+            }
+            Some(DialogMode::OpenFile) | None => OpenFile::output(dialog),
+        }
+    }
+}
+
+impl private::Sealed for OpenFile {}
+
+impl Mode for OpenFile {
+    type Output = Vec<PathBuf>;
+
+    fn output(dialog: &FileDialog) -> Result<Option<Vec<PathBuf>>, FileDialogError> {
+        let Some(selection) = dialog.selection()? else {
+            return Ok(None);
+        };
+        let files = selection.into_vec();
+        if files.is_empty() {
+            Ok(dialog.file_path_name()?.map(|path| vec![path]))
+        } else {
+            Ok(Some(files))
+        }
+    }
+}
+
+impl BuilderMode for OpenFile {
+    const MODE: DialogMode = DialogMode::OpenFile;
+}
+
+impl FileMode for OpenFile {}
+
+impl PathMode for OpenFile {}
+
+impl private::Sealed for SaveFile {}
+
+impl Mode for SaveFile {
+    type Output = SaveTarget;
+
+    fn output(dialog: &FileDialog) -> Result<Option<SaveTarget>, FileDialogError> {
+        dialog.save_target()
+    }
+}
+
+impl BuilderMode for SaveFile {
+    const MODE: DialogMode = DialogMode::SaveFile;
+}
+
+impl FileMode for SaveFile {}
+
+impl SaveMode for SaveFile {}
+
+impl PathMode for SaveFile {}
+
+impl private::Sealed for OpenDirectory {}
+
+impl Mode for OpenDirectory {
+    type Output = PathBuf;
+
+    fn output(dialog: &FileDialog) -> Result<Option<PathBuf>, FileDialogError> {
+        dialog.file_path_name()
+    }
+}
+
+impl BuilderMode for OpenDirectory {
+    const MODE: DialogMode = DialogMode::OpenDirectory;
+}
+
+impl PathMode for OpenDirectory {}
+
+#[cfg(feature = "serde")]
+impl<T> private::Sealed for OpenDocument<T> {}
+
+#[cfg(feature = "serde")]
+impl<T: DeserializeOwned> Mode for OpenDocument<T> {
+    type Output = Result<T, DocumentError>;
+
+    fn output(dialog: &FileDialog) -> Result<Option<Result<T, DocumentError>>, FileDialogError> {
+        let path = OpenFile::output(dialog)?.and_then(|paths| paths.into_iter().next());
+        Ok(path.map(|path| dialog.read_document(&path)))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T: DeserializeOwned> BuilderMode for OpenDocument<T> {
+    const MODE: DialogMode = DialogMode::OpenFile;
+}
+
+#[cfg(feature = "serde")]
+impl<T: DeserializeOwned> FileMode for OpenDocument<T> {}
+
+#[cfg(feature = "serde")]
+impl private::Sealed for SaveDocument {}
+
+#[cfg(feature = "serde")]
+impl Mode for SaveDocument {
+    type Output = Result<SaveTarget, DocumentError>;
+
+    fn output(
+        dialog: &FileDialog,
+    ) -> Result<Option<Result<SaveTarget, DocumentError>>, FileDialogError> {
+        let target = dialog.save_target()?;
+        Ok(target.map(|target| dialog.write_document(&target).map(|()| target)))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl BuilderMode for SaveDocument {
+    const MODE: DialogMode = DialogMode::SaveFile;
+}
+
+#[cfg(feature = "serde")]
+impl FileMode for SaveDocument {}
+
+#[cfg(feature = "serde")]
+impl SaveMode for SaveDocument {}
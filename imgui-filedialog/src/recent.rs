@@ -0,0 +1,284 @@
+//! Recently used files and directories.
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+
+use arcdps_imgui::{MenuItem, Ui};
+
+use crate::Selection;
+
+/// Number of entries kept per category by default.
+const DEFAULT_MAX_ENTRIES: usize = 10;
+
+/// Most recently used files and directories, newest first.
+///
+/// Every [`FileDialog`](crate::FileDialog) keeps one, updated whenever a dialog is
+/// confirmed: chosen files are added to the files, and their directory (or the
+/// chosen directory) to the directories. Show it with [`recent_files_menu`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MruList {
+    files: VecDeque<PathBuf>,
+    directories: VecDeque<PathBuf>,
+    max_files: usize,
+    max_directories: usize,
+}
+
+impl Default for MruList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MruList {
+    /// Create an empty list keeping up to 10 files and 10 directories.
+    pub fn new() -> Self {
+        Self::with_limits(DEFAULT_MAX_ENTRIES, DEFAULT_MAX_ENTRIES)
+    }
+
+    /// Create an empty list with the given caps per category.
+    pub fn with_limits(max_files: usize, max_directories: usize) -> Self {
+        Self {
+            files: VecDeque::new(),
+            directories: VecDeque::new(),
+            max_files,
+            max_directories,
+        }
+    }
+
+    /// Set the maximum number of files, dropping the oldest beyond it.
+    pub fn set_max_files(&mut self, max_files: usize) {
+        self.max_files = max_files;
+        self.files.truncate(max_files);
+    }
+
+    /// Set the maximum number of directories, dropping the oldest beyond it.
+    pub fn set_max_directories(&mut self, max_directories: usize) {
+        self.max_directories = max_directories;
+        self.directories.truncate(max_directories);
+    }
+
+    /// Get the maximum number of files.
+    pub fn max_files(&self) -> usize {
+        self.max_files
+    }
+
+    /// Get the maximum number of directories.
+    pub fn max_directories(&self) -> usize {
+        self.max_directories
+    }
+
+    /// Get the recent files, newest first.
+    pub fn files(&self) -> impl ExactSizeIterator<Item = &Path> + '_ {
+        self.files.iter().map(PathBuf::as_path)
+    }
+
+    /// Get the recent directories, newest first.
+    pub fn directories(&self) -> impl ExactSizeIterator<Item = &Path> + '_ {
+        self.directories.iter().map(PathBuf::as_path)
+    }
+
+    /// Check if there are no recent files or directories.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty() && self.directories.is_empty()
+    }
+
+    /// Mark a file as used, moving it to the front.
+    ///
+    /// This does not add its directory; see [`add_selection`](Self::add_selection).
+    pub fn add_file(&mut self, path: impl Into<PathBuf>) {
+        push_front(&mut self.files, path.into(), self.max_files);
+    }
+
+    /// Mark a directory as used, moving it to the front.
+    pub fn add_directory(&mut self, path: impl Into<PathBuf>) {
+        push_front(&mut self.directories, path.into(), self.max_directories);
+    }
+
+    /// Add all files of an accepted selection, and the directory of the first.
+    ///
+    /// The first file of the selection ends up newest.
+    pub fn add_selection(&mut self, selection: &Selection) {
+        let paths: Vec<_> = selection
+            .entries()
+            .map(|entry| entry.path.to_path_buf())
+            .collect();
+        self.add_files(paths);
+    }
+
+    /// Remove a file or directory.
+    pub fn remove(&mut self, path: &Path) {
+        self.files.retain(|p| p != path);
+        self.directories.retain(|p| p != path);
+    }
+
+    /// Remove entries that no longer exist on disk.
+    pub fn prune(&mut self) {
+        self.files.retain(|p| p.is_file());
+        self.directories.retain(|p| p.is_dir());
+    }
+
+    /// Forget all entries.
+    pub fn clear(&mut self) {
+        self.files.clear();
+        self.directories.clear();
+    }
+
+    /// Add the entries of `other` as if they were used after those of this list.
+    pub fn merge(&mut self, other: &MruList) {
+        for file in other.files.iter().rev() {
+            self.add_file(file.clone());
+        }
+        for directory in other.directories.iter().rev() {
+            self.add_directory(directory.clone());
+        }
+    }
+
+    /// Add files so the first ends up newest, along with the directory of the first.
+    pub(crate) fn add_files(&mut self, paths: Vec<PathBuf>) {
+        if let Some(directory) = paths.first().and_then(|path| path.parent()) {
+            if !directory.as_os_str().is_empty() {
+                self.add_directory(directory);
+            }
+        }
+        for path in paths.into_iter().rev() {
+            self.add_file(path);
+        }
+    }
+}
+
+fn push_front(list: &mut VecDeque<PathBuf>, path: PathBuf, max: usize) {
+    list.retain(|p| *p != path);
+    list.push_front(path);
+    list.truncate(max);
+}
+
+/// Draw a "Recent" submenu listing the entries of `recent`.
+///
+/// Call this inside a menu or menu bar. Returns the path the user clicked, which
+/// moves to the front of the list, so it can be opened without showing a dialog.
+/// Entries that no longer exist are removed when clicked and `None` is returned.
+///
+/// # Example
+///
+/// ```no_run
+/// # fn frame(ui: &arcdps_imgui::Ui, dialog: &mut imgui_filedialog::FileDialog) {
+/// use imgui_filedialog::recent_files_menu;
+///
+/// if let Some(_menu) = ui.begin_menu("File") {
+///     if let Some(path) = recent_files_menu(ui, dialog.recent_mut()) {
+///         println!("Reopening {}", path.display());
+///     }
+/// }
+/// # }
+/// ```
+pub fn recent_files_menu(ui: &Ui, recent: &mut MruList) -> Option<PathBuf> {
+    let _menu = ui.begin_menu("Recent")?;
+
+    if recent.is_empty() {
+        MenuItem::new("No recent files").enabled(false).build(ui);
+        return None;
+    }
+
+    let mut chosen = None;
+    for (i, file) in recent.files.iter().enumerate() {
+        if MenuItem::new(format!("{}##file{}", file.display(), i)).build(ui) {
+            chosen = Some((file.clone(), false));
+        }
+    }
+    if !recent.files.is_empty() && !recent.directories.is_empty() {
+        ui.separator();
+    }
+    for (i, directory) in recent.directories.iter().enumerate() {
+        if MenuItem::new(format!("{}##directory{}", directory.display(), i)).build(ui) {
+            chosen = Some((directory.clone(), true));
+        }
+    }
+    ui.separator();
+    if MenuItem::new("Clear Recent").build(ui) {
+        recent.clear();
+    }
+
+    let (path, is_directory) = chosen?;
+    let exists = if is_directory {
+        path.is_dir()
+    } else {
+        path.is_file()
+    };
+    if !exists {
+        recent.remove(&path);
+        return None;
+    }
+    if is_directory {
+        recent.add_directory(path.clone());
+    } else {
+        recent.add_file(path.clone());
+    }
+    Some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths<'a>(list: impl Iterator<Item = &'a Path>) -> Vec<PathBuf> {
+        list.map(Path::to_path_buf).collect()
+    }
+
+    fn path_vec(items: &[&str]) -> Vec<PathBuf> {
+        items.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn adding_moves_to_the_front_and_caps() {
+        let mut recent = MruList::with_limits(3, 1);
+        for file in ["a", "b", "c", "a", "d"] {
+            recent.add_file(file);
+        }
+        assert_eq!(paths(recent.files()), path_vec(&["d", "a", "c"]));
+
+        recent.add_directory("x");
+        recent.add_directory("y");
+        assert_eq!(paths(recent.directories()), path_vec(&["y"]));
+
+        recent.set_max_files(2);
+        assert_eq!(paths(recent.files()), path_vec(&["d", "a"]));
+        recent.add_file("e");
+        assert_eq!(paths(recent.files()), path_vec(&["e", "d"]));
+    }
+
+    #[test]
+    fn merged_entries_are_newer() {
+        let mut recent = MruList::new();
+        recent.add_file("a");
+        recent.add_file("b");
+        recent.add_directory("x");
+
+        let mut other = MruList::new();
+        other.add_file("c");
+        other.add_file("a");
+        other.add_directory("y");
+
+        recent.merge(&other);
+        assert_eq!(paths(recent.files()), path_vec(&["a", "c", "b"]));
+        assert_eq!(paths(recent.directories()), path_vec(&["y", "x"]));
+    }
+
+    #[test]
+    fn add_files_puts_the_first_file_and_its_directory_in_front() {
+        let mut recent = MruList::new();
+        recent.add_directory("logs");
+        recent.add_files(path_vec(&["logs/a.evtc", "other/b.evtc"]));
+        assert_eq!(
+            paths(recent.files()),
+            path_vec(&["logs/a.evtc", "other/b.evtc"])
+        );
+        assert_eq!(paths(recent.directories()), path_vec(&["logs"]));
+
+        recent.add_files(path_vec(&["c.evtc"]));
+        assert_eq!(recent.files().next(), Some(Path::new("c.evtc")));
+        assert_eq!(paths(recent.directories()), path_vec(&["logs"]));
+
+        recent.add_files(Vec::new());
+        assert_eq!(recent.files().len(), 3);
+    }
+}
@@ -0,0 +1,131 @@
+//! Result of a save dialog.
+
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use crate::{FileDialog, FileDialogError, Filter};
+
+/// What a save dialog does with a file name typed without an extension.
+///
+/// ImGuiFileDialog already replaces the extension of the typed name when a
+/// single-extension filter such as `.json` is selected, but leaves the name
+/// alone for groups such as `Logs{.evtc,.zevtc}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExtensionPolicy {
+    /// Return the name as typed.
+    #[default]
+    AsTyped,
+    /// Append the first extension of the selected filter, unless it is `.*`.
+    AppendFilterExtension,
+}
+
+/// Where the user chose to save a file.
+///
+/// Returned by polling the handle of a [`SaveFileBuilder`](crate::SaveFileBuilder).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SaveTarget {
+    path: PathBuf,
+    filter: Option<String>,
+    selected_filter: Option<Filter>,
+    exists: bool,
+}
+
+impl SaveTarget {
+    /// A target for `path` as if chosen in a dialog.
+    #[cfg(test)]
+    pub(crate) fn for_path(path: PathBuf) -> Self {
+        Self {
+            exists: path.exists(),
+            path,
+            filter: None,
+            selected_filter: None,
+        }
+    }
+
+    /// The full path of the file to write.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Take the full path of the file to write.
+    pub fn into_path(self) -> PathBuf {
+        self.path
+    }
+
+    /// The directory the file is in.
+    pub fn directory(&self) -> &Path {
+        self.path.parent().unwrap_or(Path::new(""))
+    }
+
+    /// The file name, including the extension.
+    pub fn file_name(&self) -> &OsStr {
+        self.path.file_name().unwrap_or_default()
+    }
+
+    /// The file name without the extension.
+    pub fn stem(&self) -> &OsStr {
+        self.path.file_stem().unwrap_or_default()
+    }
+
+    /// The extension without the leading `.`, as in [`Path::extension`].
+    pub fn extension(&self) -> Option<&OsStr> {
+        self.path.extension()
+    }
+
+    /// The name of the filter selected when the dialog was confirmed, as
+    /// returned by [`FileDialog::current_filter`].
+    pub fn filter(&self) -> Option<&str> {
+        self.filter.as_deref()
+    }
+
+    /// The filter selected when the dialog was confirmed.
+    ///
+    /// See [`FileDialog::selected_filter`].
+    pub fn selected_filter(&self) -> Option<&Filter> {
+        self.selected_filter.as_ref()
+    }
+
+    /// Returns `true` if the file existed when the dialog was confirmed.
+    pub fn exists(&self) -> bool {
+        self.exists
+    }
+}
+
+impl FileDialog {
+    /// Build the [`SaveTarget`] of a confirmed save dialog.
+    pub(crate) fn save_target(&self) -> Result<Option<SaveTarget>, FileDialogError> {
+        let Some(mut path) = self.file_path_name()? else {
+            return Ok(None);
+        };
+
+        let selected_filter = self.selected_filter().cloned();
+        let policy = self
+            .data
+            .map(|data| unsafe { (*data.as_ptr()).extension_policy })
+            .unwrap_or_default();
+        if policy == ExtensionPolicy::AppendFilterExtension && path.extension().is_none() {
+            let extension = selected_filter.as_ref().and_then(default_extension);
+            if let (Some(extension), Some(name)) = (extension, path.file_name()) {
+                let mut name = name.to_os_string();
+                name.push(extension);
+                path.set_file_name(name);
+            }
+        }
+
+        Ok(Some(SaveTarget {
+            exists: path.exists(),
+            filter: self.current_filter(),
+            selected_filter,
+            path,
+        }))
+    }
+}
+
+/// The extension a file saved with `filter` should get.
+fn default_extension(filter: &Filter) -> Option<&str> {
+    filter
+        .extensions()
+        .iter()
+        .map(String::as_str)
+        .find(|extension| !extension.contains('*'))
+}
@@ -0,0 +1,215 @@
+//! Extension styles: colors and icons for file types.
+
+use std::collections::BTreeMap;
+
+use crate::{FileDialog, FileDialogError};
+
+/// Display style of files matching an extension filter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtensionStyle {
+    /// RGBA color `[r, g, b, a]`.
+    pub color: [f32; 4],
+    /// Optional icon/text prefix.
+    ///
+    /// ImGuiFileDialog stores an empty icon as no icon, so
+    /// [`FileDialog::extension_style`] reads `Some("")` back as `None`.
+    pub icon: Option<String>,
+}
+
+impl ExtensionStyle {
+    /// Create a style with a color and no icon.
+    pub fn new(color: [f32; 4]) -> Self {
+        Self { color, icon: None }
+    }
+
+    /// Set the icon/text prefix. An empty icon clears it.
+    pub fn icon(mut self, icon: &str) -> Self {
+        self.icon = (!icon.is_empty()).then(|| icon.to_owned());
+        self
+    }
+}
+
+/// A set of extension styles that can be applied to a [`FileDialog`] in one call.
+///
+/// ImGuiFileDialog only offers a way to clear all styles, so keep the registry
+/// around and use [`FileDialog::reset_extension_styles`] to restore it.
+///
+/// # Example
+///
+/// ```no_run
+/// use imgui_filedialog::{ExtensionStyle, ExtensionStyles, FileDialog};
+///
+/// # fn main() -> Result<(), imgui_filedialog::FileDialogError> {
+/// let styles = ExtensionStyles::new()
+///     .with(".evtc", ExtensionStyle::new([1.0, 0.8, 0.0, 1.0]).icon("[log]"))
+///     .with(".zevtc", ExtensionStyle::new([1.0, 0.6, 0.0, 1.0]).icon("[log]"));
+///
+/// let mut dialog = FileDialog::new();
+/// dialog.apply_extension_styles(&styles)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExtensionStyles {
+    styles: BTreeMap<String, ExtensionStyle>,
+}
+
+/// A difference between two [`ExtensionStyles`], see [`ExtensionStyles::diff`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum StyleChange<'a> {
+    /// The extension only has a style in the other registry.
+    Added {
+        extension: &'a str,
+        style: &'a ExtensionStyle,
+    },
+    /// The extension only has a style in this registry.
+    Removed {
+        extension: &'a str,
+        style: &'a ExtensionStyle,
+    },
+    /// The extension has different styles in both registries.
+    Changed {
+        extension: &'a str,
+        old: &'a ExtensionStyle,
+        new: &'a ExtensionStyle,
+    },
+}
+
+impl ExtensionStyles {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a style, consuming and returning the registry.
+    pub fn with(mut self, extension: &str, style: ExtensionStyle) -> Self {
+        self.insert(extension, style);
+        self
+    }
+
+    /// Add or replace the style of an extension, returning the previous one.
+    pub fn insert(&mut self, extension: &str, style: ExtensionStyle) -> Option<ExtensionStyle> {
+        self.styles.insert(extension.to_owned(), style)
+    }
+
+    /// Remove the style of an extension, returning it.
+    pub fn remove(&mut self, extension: &str) -> Option<ExtensionStyle> {
+        self.styles.remove(extension)
+    }
+
+    /// Get the style of an extension.
+    pub fn get(&self, extension: &str) -> Option<&ExtensionStyle> {
+        self.styles.get(extension)
+    }
+
+    /// Number of styled extensions.
+    pub fn len(&self) -> usize {
+        self.styles.len()
+    }
+
+    /// Returns `true` if no extension is styled.
+    pub fn is_empty(&self) -> bool {
+        self.styles.is_empty()
+    }
+
+    /// Iterate over the styled extensions in sorted order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &ExtensionStyle)> + '_ {
+        self.styles.iter().map(|(ext, style)| (ext.as_str(), style))
+    }
+
+    /// List the changes that turn this registry into `other`.
+    pub fn diff<'a>(&'a self, other: &'a ExtensionStyles) -> Vec<StyleChange<'a>> {
+        let mut changes = Vec::new();
+        for (extension, old) in self.iter() {
+            match other.get(extension) {
+                None => changes.push(StyleChange::Removed {
+                    extension,
+                    style: old,
+                }),
+                Some(new) if new != old => changes.push(StyleChange::Changed {
+                    extension,
+                    old,
+                    new,
+                }),
+                Some(_) => {}
+            }
+        }
+        for (extension, style) in other.iter() {
+            if !self.styles.contains_key(extension) {
+                changes.push(StyleChange::Added { extension, style });
+            }
+        }
+        changes
+    }
+}
+
+impl FileDialog {
+    /// Set the style of every extension in `styles`.
+    ///
+    /// Styles already set for other extensions are kept.
+    pub fn apply_extension_styles(
+        &mut self,
+        styles: &ExtensionStyles,
+    ) -> Result<(), FileDialogError> {
+        for (extension, style) in styles.iter() {
+            self.set_extension_style(extension, style)?;
+        }
+        Ok(())
+    }
+
+    /// Clear all extension styles, then apply `styles`.
+    pub fn reset_extension_styles(
+        &mut self,
+        styles: &ExtensionStyles,
+    ) -> Result<(), FileDialogError> {
+        self.clear_extension_infos();
+        self.apply_extension_styles(styles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn style(red: f32) -> ExtensionStyle {
+        ExtensionStyle::new([red, 0.0, 0.0, 1.0])
+    }
+
+    #[test]
+    fn diff_lists_added_removed_and_changed_extensions() {
+        let old = ExtensionStyles::new()
+            .with(".evtc", style(1.0))
+            .with(".log", style(0.5))
+            .with(".zip", style(0.2));
+        let new = ExtensionStyles::new()
+            .with(".evtc", style(1.0).icon("[log]"))
+            .with(".txt", style(0.3))
+            .with(".zip", style(0.2));
+
+        assert_eq!(
+            old.diff(&new),
+            [
+                StyleChange::Changed {
+                    extension: ".evtc",
+                    old: &style(1.0),
+                    new: &style(1.0).icon("[log]"),
+                },
+                StyleChange::Removed {
+                    extension: ".log",
+                    style: &style(0.5),
+                },
+                StyleChange::Added {
+                    extension: ".txt",
+                    style: &style(0.3),
+                },
+            ]
+        );
+        assert!(new.diff(&new).is_empty());
+    }
+
+    #[test]
+    fn empty_icons_are_no_icon() {
+        assert_eq!(style(1.0).icon("").icon, None);
+        assert_eq!(style(1.0).icon("[log]").icon("").icon, None);
+    }
+}
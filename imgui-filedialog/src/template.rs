@@ -0,0 +1,143 @@
+//! Default file names generated from a template.
+
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Number of names tried before giving up on finding a free one.
+const MAX_PROBES: u32 = 10_000;
+
+/// Expand `template` into a file name that does not exist in `directory` yet.
+///
+/// `{date}` becomes `YYYY-MM-DD` and `{time}` `HH-MM-SS`, both in UTC rather
+/// than local time. `{n}` counts up from 1 until the name is free. Without
+/// `{n}`, a counter is added before the extension on collision, e.g.
+/// `report (2).csv`. Returns `None` if no free name is found.
+pub(crate) fn expand_file_name(
+    template: &str,
+    directory: &Path,
+    now: SystemTime,
+) -> Option<String> {
+    expand(template, now, |name| directory.join(name).exists())
+}
+
+fn expand(template: &str, now: SystemTime, is_taken: impl Fn(&str) -> bool) -> Option<String> {
+    let (date, time) = format_utc(now);
+    let template = template.replace("{date}", &date).replace("{time}", &time);
+
+    let candidate = |n: u32| {
+        if template.contains("{n}") {
+            template.replace("{n}", &n.to_string())
+        } else if n == 1 {
+            template.clone()
+        } else {
+            with_counter(&template, n)
+        }
+    };
+
+    (1..=MAX_PROBES).map(candidate).find(|name| !is_taken(name))
+}
+
+/// Insert ` (n)` before the extension of `name`.
+fn with_counter(name: &str, n: u32) -> String {
+    let path = Path::new(name);
+    match (path.file_stem(), path.extension()) {
+        (Some(stem), Some(extension)) => format!(
+            "{} ({}).{}",
+            stem.to_string_lossy(),
+            n,
+            extension.to_string_lossy()
+        ),
+        _ => format!("{} ({})", name, n),
+    }
+}
+
+/// Format `time` as a UTC date and time for use in file names.
+fn format_utc(time: SystemTime) -> (String, String) {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    let secs_of_day = secs % 86_400;
+
+    (
+        format!("{:04}-{:02}-{:02}", year, month, day),
+        format!(
+            "{:02}-{:02}-{:02}",
+            secs_of_day / 3600,
+            secs_of_day / 60 % 60,
+            secs_of_day % 60
+        ),
+    )
+}
+
+/// Convert days since 1970-01-01 to a proleptic Gregorian `(year, month, day)`.
+///
+/// See <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn converts_days_to_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(civil_from_days(11_017), (2000, 3, 1));
+        assert_eq!(civil_from_days(19_782), (2024, 2, 29));
+    }
+
+    #[test]
+    fn formats_date_and_time_in_utc() {
+        let now = at(1_709_211_909);
+        assert_eq!(
+            format_utc(now),
+            ("2024-02-29".to_owned(), "13-05-09".to_owned())
+        );
+        assert_eq!(
+            expand("log-{date}-{time}.txt", now, |_| false).as_deref(),
+            Some("log-2024-02-29-13-05-09.txt")
+        );
+    }
+
+    #[test]
+    fn counts_up_until_a_name_is_free() {
+        let taken = ["report.csv", "report (2).csv", "export-1.csv"];
+        let is_taken = |name: &str| taken.contains(&name);
+        assert_eq!(
+            expand("report.csv", at(0), is_taken).as_deref(),
+            Some("report (3).csv")
+        );
+        assert_eq!(
+            expand("export-{n}.csv", at(0), is_taken).as_deref(),
+            Some("export-2.csv")
+        );
+        assert_eq!(
+            expand("notes", at(0), |name| name == "notes").as_deref(),
+            Some("notes (2)")
+        );
+    }
+
+    #[test]
+    fn gives_up_when_every_name_is_taken() {
+        assert_eq!(expand("report.csv", at(0), |_| true), None);
+        assert_eq!(expand("export-{n}.csv", at(0), |_| true), None);
+    }
+}
@@ -0,0 +1,333 @@
+//! Atomic writes to the target of a save dialog.
+
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use crate::SaveTarget;
+
+/// Number of temporary file names tried before giving up.
+const MAX_TEMP_ATTEMPTS: u32 = 100;
+
+/// Distinguishes temporary files of concurrent writes within the process.
+static TEMP_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+/// Error of [`SaveTarget::write_atomic`] and [`SaveTarget::write_bytes`].
+///
+/// The target is left untouched whenever one of these is returned.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum WriteError {
+    /// The temporary file next to the target could not be created.
+    CreateTemp {
+        /// Path of the temporary file.
+        path: PathBuf,
+        /// The underlying error.
+        source: io::Error,
+    },
+    /// Writing the contents failed.
+    Write(io::Error),
+    /// Flushing the contents to disk failed.
+    Sync(io::Error),
+    /// The permissions of the replaced file could not be copied.
+    Permissions(io::Error),
+    /// The temporary file could not be renamed over the target.
+    Rename {
+        /// Path of the target.
+        path: PathBuf,
+        /// The underlying error.
+        source: io::Error,
+    },
+}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CreateTemp { path, source } => {
+                write!(f, "cannot create {}: {}", path.display(), source)
+            }
+            Self::Write(source) => write!(f, "cannot write file: {}", source),
+            Self::Sync(source) => write!(f, "cannot flush file to disk: {}", source),
+            Self::Permissions(source) => write!(f, "cannot keep file permissions: {}", source),
+            Self::Rename { path, source } => {
+                write!(f, "cannot replace {}: {}", path.display(), source)
+            }
+        }
+    }
+}
+
+impl Error for WriteError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::CreateTemp { source, .. }
+            | Self::Write(source)
+            | Self::Sync(source)
+            | Self::Permissions(source)
+            | Self::Rename { source, .. } => Some(source),
+        }
+    }
+}
+
+impl SaveTarget {
+    /// Write the file through a temporary file, so it is either fully replaced
+    /// or left untouched.
+    ///
+    /// `write` fills a temporary file next to the target, which is then synced
+    /// to disk and renamed over the target. When a file is replaced, its
+    /// permissions carry over. If the target is a symlink, the file it points to
+    /// is replaced and the link is kept; a dangling link is replaced by the file.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::io::Write;
+    /// use imgui_filedialog::{DialogOutcome, FileDialog};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let mut dialog = FileDialog::new();
+    /// let handle = dialog.save_file().filters(".csv").confirm_overwrite().build("export")?;
+    ///
+    /// // Every frame:
+    /// if let DialogOutcome::Accepted { value: target, .. } = dialog.poll(&handle, [400.0, 300.0], [800.0, 600.0]) {
+    ///     target.write_atomic(|w| {
+    ///         writeln!(w, "time,damage")?;
+    ///         writeln!(w, "0,1234")
+    ///     })?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn write_atomic<F>(&self, write: F) -> Result<(), WriteError>
+    where
+        F: FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+    {
+        let target = &resolve_symlink(self.path());
+        let permissions = fs::metadata(target).ok().map(|meta| meta.permissions());
+        let (temp_path, file) = create_temp(target)?;
+
+        let result = (|| {
+            let mut writer = BufWriter::new(file);
+            write(&mut writer).map_err(WriteError::Write)?;
+            let file = writer
+                .into_inner()
+                .map_err(|e| WriteError::Write(e.into_error()))?;
+            file.sync_all().map_err(WriteError::Sync)?;
+            drop(file);
+
+            if let Some(permissions) = permissions {
+                fs::set_permissions(&temp_path, permissions).map_err(WriteError::Permissions)?;
+            }
+            fs::rename(&temp_path, target).map_err(|source| WriteError::Rename {
+                path: target.to_owned(),
+                source,
+            })
+        })();
+
+        match result {
+            Ok(()) => {
+                sync_directory(target);
+                Ok(())
+            }
+            Err(e) => {
+                let _ = fs::remove_file(&temp_path);
+                Err(e)
+            }
+        }
+    }
+
+    /// Write `bytes` to the file, see [`write_atomic`](Self::write_atomic).
+    pub fn write_bytes(&self, bytes: &[u8]) -> Result<(), WriteError> {
+        self.write_atomic(|w| w.write_all(bytes))
+    }
+}
+
+/// The file a symlink at `path` points to, or `path` itself.
+///
+/// Renaming over a symlink would replace the link instead of the file.
+fn resolve_symlink(path: &Path) -> PathBuf {
+    match fs::symlink_metadata(path) {
+        Ok(meta) if meta.file_type().is_symlink() => {
+            fs::canonicalize(path).unwrap_or_else(|_| path.to_owned())
+        }
+        _ => path.to_owned(),
+    }
+}
+
+/// The directory `target` is in, `.` for a bare file name.
+fn directory_of(target: &Path) -> &Path {
+    match target.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    }
+}
+
+/// Create a new temporary file in the directory of `target`.
+fn create_temp(target: &Path) -> Result<(PathBuf, File), WriteError> {
+    let directory = directory_of(target);
+    let name = target.file_name().unwrap_or_default().to_string_lossy();
+
+    let mut attempt = 0;
+    loop {
+        let counter = TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+        let path = directory.join(format!(".{}.{}-{}.tmp", name, process::id(), counter));
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists && attempt < MAX_TEMP_ATTEMPTS => {
+                attempt += 1;
+            }
+            Err(source) => return Err(WriteError::CreateTemp { path, source }),
+        }
+    }
+}
+
+/// Make the rename durable by syncing the directory entry, where supported.
+fn sync_directory(target: &Path) {
+    #[cfg(unix)]
+    {
+        if let Ok(directory) = File::open(directory_of(target)) {
+            let _ = directory.sync_all();
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = target;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh directory under the system temp directory, removed on drop.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> Self {
+            static COUNTER: AtomicU32 = AtomicU32::new(0);
+            let path = std::env::temp_dir().join(format!(
+                "imgui-filedialog-write-{}-{}",
+                process::id(),
+                COUNTER.fetch_add(1, Ordering::Relaxed)
+            ));
+            fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+
+        fn target(&self, name: &str) -> SaveTarget {
+            SaveTarget::for_path(self.0.join(name))
+        }
+
+        fn entries(&self) -> Vec<String> {
+            let mut names: Vec<_> = fs::read_dir(&self.0)
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+                .collect();
+            names.sort();
+            names
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn writes_a_new_file() {
+        let dir = TempDir::new();
+        dir.target("new.txt").write_bytes(b"hello").unwrap();
+        assert_eq!(fs::read(dir.0.join("new.txt")).unwrap(), b"hello");
+        assert_eq!(dir.entries(), ["new.txt"]);
+    }
+
+    #[test]
+    fn replaces_an_existing_file() {
+        let dir = TempDir::new();
+        let path = dir.0.join("existing.txt");
+        fs::write(&path, "old contents that are longer").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+        }
+
+        dir.target("existing.txt").write_bytes(b"new").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"new");
+        assert_eq!(dir.entries(), ["existing.txt"]);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o640);
+        }
+    }
+
+    #[test]
+    fn failed_write_keeps_the_original() {
+        let dir = TempDir::new();
+        let path = dir.0.join("keep.txt");
+        fs::write(&path, "original").unwrap();
+
+        let result = dir.target("keep.txt").write_atomic(|w| {
+            w.write_all(b"partial")?;
+            Err(io::ErrorKind::BrokenPipe.into())
+        });
+        assert!(matches!(result, Err(WriteError::Write(_))));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "original");
+        assert_eq!(dir.entries(), ["keep.txt"]);
+    }
+
+    #[test]
+    fn failed_rename_removes_the_temp_file() {
+        let dir = TempDir::new();
+        fs::create_dir(dir.0.join("occupied")).unwrap();
+        fs::write(dir.0.join("occupied").join("file"), "").unwrap();
+
+        let result = dir.target("occupied").write_bytes(b"data");
+        assert!(matches!(result, Err(WriteError::Rename { .. })));
+        assert_eq!(dir.entries(), ["occupied"]);
+    }
+
+    #[test]
+    fn missing_directory_fails_to_create_the_temp_file() {
+        let dir = TempDir::new();
+        let result = dir.target("missing/file.txt").write_bytes(b"data");
+        assert!(matches!(result, Err(WriteError::CreateTemp { .. })));
+        assert!(dir.entries().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn writes_through_symlinks() {
+        let dir = TempDir::new();
+        let real = dir.0.join("real.txt");
+        let link = dir.0.join("link.txt");
+        fs::write(&real, "old").unwrap();
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+
+        dir.target("link.txt").write_bytes(b"new").unwrap();
+        assert!(fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(fs::read(&real).unwrap(), b"new");
+        assert_eq!(dir.entries(), ["link.txt", "real.txt"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn replaces_dangling_symlinks() {
+        let dir = TempDir::new();
+        let link = dir.0.join("dangling.txt");
+        std::os::unix::fs::symlink(dir.0.join("gone.txt"), &link).unwrap();
+
+        dir.target("dangling.txt").write_bytes(b"new").unwrap();
+        assert!(fs::symlink_metadata(&link).unwrap().is_file());
+        assert_eq!(fs::read(&link).unwrap(), b"new");
+    }
+}
@@ -0,0 +1,172 @@
+use imgui_filedialog::{Filter, FilterError, FilterErrorKind, FilterSet};
+use proptest::prelude::*;
+
+fn error(s: &str) -> FilterError {
+    FilterSet::parse(s).expect_err(s)
+}
+
+#[test]
+fn parses_all_forms() {
+    let set = FilterSet::parse("Source{.rs,.toml},.md,.*").unwrap();
+    assert_eq!(
+        set.filters(),
+        [
+            Filter::group("Source", [".rs", ".toml"]),
+            Filter::extension(".md"),
+            Filter::all(),
+        ]
+    );
+    assert!(set.filters()[2].is_all());
+}
+
+#[test]
+fn case_insensitive_filters_parse_as_groups() {
+    let set = FilterSet::new().with(Filter::extension(".txt").case_insensitive());
+    let parsed = FilterSet::parse(&set.to_string()).unwrap();
+    assert_eq!(parsed.filters(), [Filter::group(".txt", [".txt", ".TXT"])]);
+    assert_eq!(parsed.to_string(), set.to_string());
+    assert_eq!(parsed.find(".txt").unwrap().name(), set.filters()[0].name());
+}
+
+#[test]
+fn reports_unbalanced_braces() {
+    for (s, position) in [
+        ("Images{.png", 6),
+        ("Images.png}", 10),
+        ("A{.a{.b}}", 4),
+        ("A{.a}{.b}", 5),
+        (".a,}", 3),
+    ] {
+        let e = error(s);
+        assert_eq!(e.kind, FilterErrorKind::UnbalancedBrace, "{}", s);
+        assert_eq!(e.position, position, "{}", s);
+    }
+}
+
+#[test]
+fn reports_empty_groups_and_entries() {
+    assert_eq!(error("").kind, FilterErrorKind::Empty);
+
+    let e = error(".a,Empty{}");
+    assert_eq!((e.kind, e.position), (FilterErrorKind::EmptyGroup, 9));
+
+    let e = error("{.a}");
+    assert_eq!((e.kind, e.position), (FilterErrorKind::EmptyName, 0));
+
+    for (s, position) in [(".a,,.b", 3), (".a,", 3), ("A{.a,}", 5), (".", 0)] {
+        let e = error(s);
+        assert_eq!(e.kind, FilterErrorKind::EmptyExtension, "{}", s);
+        assert_eq!(e.position, position, "{}", s);
+    }
+}
+
+#[test]
+fn reports_missing_dots() {
+    let e = error(".png,jpg");
+    assert_eq!((e.kind, e.position), (FilterErrorKind::MissingDot, 5));
+
+    let e = error("Images{.png, .jpg}");
+    assert_eq!((e.kind, e.position), (FilterErrorKind::MissingDot, 12));
+}
+
+#[test]
+fn reports_duplicates() {
+    let e = error(".png,Images{.png,.jpg},.png");
+    assert_eq!(e.kind, FilterErrorKind::Duplicate(".png".into()));
+    assert_eq!(e.position, 23);
+
+    let e = error("Images{.png,.jpg,.png}");
+    assert_eq!(e.kind, FilterErrorKind::Duplicate(".png".into()));
+    assert_eq!(e.position, 17);
+
+    let e = error("A{.a},A{.b}");
+    assert_eq!(e.kind, FilterErrorKind::Duplicate("A".into()));
+    assert_eq!(e.position, 6);
+}
+
+#[test]
+fn reports_trailing_characters() {
+    let e = error("A{.a}b,.c");
+    assert_eq!(
+        (e.kind, e.position),
+        (FilterErrorKind::InvalidCharacter('b'), 5)
+    );
+}
+
+#[test]
+fn typed_sets_are_validated() {
+    let set = FilterSet::new().with(Filter::group("Images", [".png", "jpg"]));
+    let e = set.validate().unwrap_err();
+    assert_eq!((e.kind, e.position), (FilterErrorKind::MissingDot, 12));
+
+    let set = FilterSet::new().with(Filter::group("A,B", [".a"]));
+    assert_eq!(
+        set.validate().unwrap_err().kind,
+        FilterErrorKind::InvalidCharacter(',')
+    );
+}
+
+fn extension() -> impl Strategy<Value = String> {
+    prop_oneof![
+        Just(".*".to_owned()),
+        "\\.[a-zA-Z0-9_]{1,5}",
+        "\\.[a-z]{1,3}\\.[a-z]{1,3}",
+    ]
+}
+
+fn filter() -> impl Strategy<Value = Filter> {
+    let single = extension().prop_map(Filter::extension);
+    let group = (
+        "[A-Za-z][A-Za-z0-9 ._-]{0,12}",
+        prop::collection::vec(extension(), 1..5),
+    )
+        .prop_map(|(name, extensions)| Filter::group(name, extensions));
+    (prop_oneof![single, group], any::<bool>()).prop_map(|(filter, case_insensitive)| {
+        if case_insensitive {
+            filter.case_insensitive()
+        } else {
+            filter
+        }
+    })
+}
+
+proptest! {
+    #[test]
+    fn valid_sets_round_trip(filters in prop::collection::vec(filter(), 1..6)) {
+        let set: FilterSet = filters.into_iter().collect();
+        prop_assume!(set.validate().is_ok());
+
+        let rendered = set.to_string();
+        let parsed = FilterSet::parse(&rendered).unwrap();
+        prop_assert_eq!(parsed.to_string(), rendered.as_str());
+        prop_assert_eq!(parsed.len(), set.len());
+        for filter in set.filters() {
+            prop_assert!(parsed.find(filter.name()).is_some());
+        }
+    }
+
+    #[test]
+    fn parsing_is_exact_or_positioned(s in "[.,{}aAb* ]{0,24}") {
+        check_parse(&s)?;
+    }
+
+    #[test]
+    fn parsing_never_panics(s in "\\PC{0,32}") {
+        check_parse(&s)?;
+    }
+}
+
+/// A string either parses and renders back unchanged, or fails inside it.
+fn check_parse(s: &str) -> Result<(), TestCaseError> {
+    match FilterSet::parse(s) {
+        Ok(set) => {
+            prop_assert_eq!(set.to_string(), s);
+            prop_assert_eq!(FilterSet::parse(&set.to_string()), Ok(set));
+        }
+        Err(e) => {
+            prop_assert!(e.position <= s.len());
+            prop_assert!(s.is_char_boundary(e.position));
+        }
+    }
+    Ok(())
+}